@@ -1,5 +1,8 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+extern crate alloc;
+
+use alloc::vec::Vec;
 use codec::{Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
 pub use pallet::*;
 pub mod weights;
@@ -8,7 +11,10 @@ pub use weights::*;
 pub mod utils;
 use crate::utils::*;
 
+pub mod migrations;
+
 use frame_support::sp_runtime::RuntimeDebug;
+use frame_support::{sp_runtime::Percent, traits::ConstU32, BoundedVec};
 use scale_info::TypeInfo;
 #[cfg(test)]
 mod mock;
@@ -29,6 +35,115 @@ const NFT_SPAWN_COOLDOWN_BLOCKS: u32 = 10;
 /// Maximum percentage of the DOT prize pool that can be emitted as DOT asteroids
 const DOT_EMISSION_LIMIT_RATIO: u64 = 10; // 10%
 const ENERGY_ASTEROID_REWARD: u32 = 15;
+/// Maximum number of ships that can contend for the same asteroid within one block
+const MAX_CLAIMANTS_PER_ASTEROID: u32 = 16;
+/// Number of blocks a flight takes from start to landing
+const FLIGHT_DURATION_BLOCKS: u32 = 2;
+/// Energy charged for changing course in the middle of a flight
+const REDIRECT_ENERGY_PENALTY: u32 = 10;
+/// Maximum number of waypoints in a planned route
+const MAX_ROUTE_WAYPOINTS: u32 = 8;
+/// Energy charged for every leg of a planned route
+const ROUTE_LEG_ENERGY_COST: u32 = 5;
+/// Default number of asteroids a sweeping ship collects along its path in one flight
+const SWEEP_COLLECT_CAP: u32 = 3;
+/// Gold burned for the first level of an upgrade, every next level costs this much more
+const UPGRADE_BASE_COST: u64 = 10;
+/// Extra starting energy per `MaxEnergy` upgrade level
+const UPGRADE_ENERGY_BONUS: u32 = 20;
+/// Maximum number of ships an account can have on the map at the same time
+const MAX_FLEET_SIZE: u32 = 3;
+/// Default number of Gold and DOT units a ship can carry before it has to deposit them at a base
+const CARGO_CAPACITY: u32 = 10;
+/// Default share of the unbanked cargo that ends up in the wreck of a ship that ran out of energy, in percent
+const WRECK_SHARE_PERCENT: u8 = 50;
+/// Default number of blocks a wreck stays on the map
+const WRECK_TTL_BLOCKS: u32 = 20;
+/// Default number of blocks an account has to wait before `start_game` after losing a ship
+const RESPAWN_COOLDOWN_BLOCKS: u32 = 10;
+/// Number of blocks a power-up stays active after it is collected
+const POWER_UP_DURATION_BLOCKS: u32 = 10;
+/// Energy drained from a ship that collects a Radiation asteroid without a shield
+const RADIATION_ENERGY_DRAIN: u32 = 20;
+/// Maximum number of black holes on the map
+const MAX_BLACK_HOLES: u32 = 8;
+/// Ships landing this close to a black hole are pulled one tile towards it
+const BLACK_HOLE_PULL_RADIUS: u32 = 2;
+/// Maximum number of hazard zones on the map
+const MAX_HAZARD_ZONES: u32 = 8;
+/// Bytes of a row of the blocked tiles bitmap, limiting blocked tiles to maps up to 256 tiles wide
+const MAX_MAP_ROW_BYTES: u32 = 32;
+/// Maximum number of tiles changed by one `admin_set_blocked_tiles` call
+const MAX_BLOCKED_TILES_PER_CALL: u32 = 256;
+/// Maximum number of rows of an uploaded map, which is also the largest map size a layout can have
+const MAX_MAP_ROWS: u32 = MAX_MAP_ROW_BYTES * 8;
+/// Number of ships or asteroids checked every block while a shrunk map is cleaned up
+const MAP_CLEANUP_BATCH: usize = 64;
+/// Maximum length of the storage key a map cleanup or the station batch resumes from
+const MAX_CLEANUP_CURSOR_LEN: u32 = 128;
+/// Game played on the shared map that `start_game` joins. Arenas get the ids after it.
+const MAIN_GAME: GameId = 0;
+/// Maximum number of arenas open at the same time
+const MAX_ARENAS: u32 = 16;
+/// Maximum number of arenas created by accounts open at the same time. The other slots are kept for
+/// queue matches and duels.
+const MAX_USER_ARENAS: u32 = 8;
+/// Maximum number of arenas an account can have open at the same time
+const MAX_ARENAS_PER_CREATOR: u32 = 2;
+/// Maximum number of asteroids an arena creator can ask for
+const MAX_ARENA_ASTEROIDS: u32 = 100;
+/// Maximum number of accounts registered for a tournament
+const MAX_TOURNAMENT_PLAYERS: u32 = 32;
+/// Maximum number of paid places of a tournament
+const MAX_PAYOUT_PLACES: u32 = 8;
+/// Maximum number of accounts waiting in the queue of a match mode
+const MAX_QUEUE_LEN: u32 = 64;
+/// Maximum number of players of a match started from the queue
+const MAX_MATCH_PLAYERS: u32 = 8;
+/// Number of blocks a match started from the queue lasts at most
+const MATCH_DURATION: u32 = 300;
+/// Number of blocks a tournament or a duel may last at most
+const MAX_MATCH_DURATION: u32 = 14_400;
+/// Number of blocks a challenge waits to be accepted before the wager is refunded
+const CHALLENGE_EXPIRY: u32 = 100;
+/// Maximum number of challenges issued in the same block, all of them expire together
+const MAX_CHALLENGES_PER_BLOCK: u32 = 16;
+/// Number of blocks between two shrinks of a battle royale safe zone
+const SAFE_ZONE_SHRINK_INTERVAL: u32 = 10;
+/// Tiles the radius of a safe zone loses on every shrink
+const SAFE_ZONE_SHRINK_STEP: u32 = 2;
+/// Energy lost every block by a ship outside the safe zone, on top of its own depletion
+const SAFE_ZONE_ENERGY_DRAIN: u32 = 10;
+/// Width and height in tiles of the map region owned through `claim_tile`
+const TERRITORY_SIZE: u32 = 8;
+/// Minimum Gold staked to claim a free region
+const MIN_TERRITORY_STAKE: u64 = 10;
+/// Default share of the Gold collected in someone else's region paid to its owner, in percent
+const TERRITORY_TAX_PERCENT: u8 = 20;
+/// Gold paid to build a station, every upgrade costs this times the next level
+const STATION_BUILD_COST: u64 = 50;
+/// Highest level a station can be upgraded to
+const MAX_STATION_LEVEL: u8 = 5;
+/// Maximum number of stations owned by an account
+const MAX_STATIONS_PER_ACCOUNT: u32 = 3;
+/// Number of blocks between two yields of a station
+const STATION_YIELD_INTERVAL: u32 = 10;
+/// Gold banked by a Gold station per level on every yield
+const STATION_GOLD_YIELD: u64 = 1;
+/// Energy stored by an Energy station per level on every yield
+const STATION_ENERGY_YIELD: u32 = 20;
+/// Energy an Energy station stores at most per level
+const STATION_ENERGY_CAP: u32 = 100;
+/// Energy every station gives per level to each ship of its owner docked on it, on every yield
+const STATION_RECHARGE_ENERGY: u32 = 5;
+/// Maximum number of stations visited by `on_initialize` in one block
+const STATION_BATCH: usize = 32;
+/// Maximum number of spawn zones of an uploaded map
+const MAX_SPAWN_ZONES: u32 = 16;
+/// Maximum number of asteroid kinds with a custom weight in a spawn zone
+const MAX_ZONE_ASTEROID_KINDS: u32 = 16;
+/// Maximum number of base tiles of an uploaded map
+const MAX_BASE_TILES: u32 = 16;
 
 #[derive(
     Encode,
@@ -75,12 +190,20 @@ pub struct Flight<BlockNumber> {
     RuntimeDebug,
     TypeInfo,
 )]
-pub struct Starship {
+pub struct Starship<BlockNumber> {
+    /// Game the ship plays in, `MAIN_GAME` or an arena
+    pub game: GameId,
     pub pos: Coord,
     pub energy: Energy,
     pub nft_skin: u32,
+    pub collection_mode: CollectionMode,
+    pub class: ShipClass,
+    pub stats: ShipStats,
+    pub cargo: Cargo,
+    pub effects: Effects<BlockNumber>,
 }
 
+/// Timed effect granted by collecting a power-up asteroid.
 #[derive(
     Encode,
     Decode,
@@ -93,634 +216,4989 @@ pub struct Starship {
     MaxEncodedLen,
     DecodeWithMemTracking,
 )]
-pub enum AsteroidKind {
-    Energy = 0,
-    Gold = 1,
-    Dot0 = 2,
-    Dot1 = 3,
-    Dot2 = 4,
-    Nft0 = 5, //Uncommon
-    Nft1 = 6, //Rare
-    Nft2 = 7, //Mystical
+pub enum PowerUp {
+    /// The ship does not lose energy
+    Shield = 0,
+    /// Flights take half the time
+    Booster = 1,
+    /// Asteroids next to the landing tile are collected as well
+    Magnet = 2,
 }
 
-pub type AsteroidType = AsteroidKind;
-pub type Energy = u32;
-
-#[frame_support::pallet]
-pub mod pallet {
-
-    use super::*;
-    use frame_support::{pallet_prelude::*, runtime_print, storage::child::get};
-    use frame_system::pallet_prelude::*;
+impl PowerUp {
+    pub const ALL: [PowerUp; 3] = [PowerUp::Shield, PowerUp::Booster, PowerUp::Magnet];
+}
 
-    #[pallet::pallet]
-    pub struct Pallet<T>(_);
+/// Power-up effects of a ship, each with the last block it is active in.
+#[derive(
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    Default,
+    MaxEncodedLen,
+    Clone,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+)]
+pub struct Effects<BlockNumber> {
+    pub shield: Option<BlockNumber>,
+    pub booster: Option<BlockNumber>,
+    pub magnet: Option<BlockNumber>,
+}
 
-    #[pallet::config]
-    pub trait Config: frame_system::Config {
-        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
-        type WeightInfo: WeightInfo;
+impl<BlockNumber: Copy + PartialOrd> Effects<BlockNumber> {
+    fn expiry_mut(&mut self, power_up: PowerUp) -> &mut Option<BlockNumber> {
+        match power_up {
+            PowerUp::Shield => &mut self.shield,
+            PowerUp::Booster => &mut self.booster,
+            PowerUp::Magnet => &mut self.magnet,
+        }
     }
 
-    type UserAccount<T> = <T as frame_system::Config>::AccountId;
+    pub fn is_active(&self, power_up: PowerUp, now: BlockNumber) -> bool {
+        let expiry = match power_up {
+            PowerUp::Shield => self.shield,
+            PowerUp::Booster => self.booster,
+            PowerUp::Magnet => self.magnet,
+        };
+        expiry.is_some_and(|until| now <= until)
+    }
 
-    #[pallet::storage]
-    pub type MapSize<T> = StorageValue<_, u32>;
+    /// Activates the power-up until block `until`. Collecting the same power-up again extends it.
+    pub fn activate(&mut self, power_up: PowerUp, until: BlockNumber) {
+        let expiry = self.expiry_mut(power_up);
+        if expiry.is_none_or(|current| current < until) {
+            *expiry = Some(until);
+        }
+    }
 
-    #[pallet::storage]
-    pub type MaxAsteroidsCount<T> = StorageValue<_, u32>;
+    /// Removes the effects that ended before `now` and returns them.
+    pub fn clear_expired(&mut self, now: BlockNumber) -> Vec<PowerUp> {
+        let mut expired = Vec::new();
+        for power_up in PowerUp::ALL {
+            let expiry = self.expiry_mut(power_up);
+            if expiry.is_some_and(|until| until < now) {
+                *expiry = None;
+                expired.push(power_up);
+            }
+        }
+        expired
+    }
+}
 
-    #[pallet::storage]
-    pub type PlayersCount<T> = StorageValue<_, u32, ValueQuery>;
+/// Gold and DOT carried by a ship. They reach `AccountResources` only when deposited at a base tile,
+/// and are dropped as a wreck if the ship runs out of energy.
+#[derive(
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    Default,
+    MaxEncodedLen,
+    Clone,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+)]
+pub struct Cargo {
+    pub gold: u64,
+    pub dot: u64,
+}
 
-    // #[pallet::storage]
-    //  pub type AsteroidIds<T> = StorageValue<_, u64, ValueQuery>;
+impl Cargo {
+    pub fn total(&self) -> u64 {
+        self.gold.saturating_add(self.dot)
+    }
 
-    #[pallet::storage]
-    pub type Asteroids<T: Config> =
-        StorageMap<_, Twox64Concat, Coord, (AsteroidType, BlockNumberFor<T>), OptionQuery>;
+    pub fn is_empty(&self) -> bool {
+        self.total() == 0
+    }
 
-    #[pallet::storage]
-    pub type Flights<T: Config> =
-        StorageMap<_, Twox64Concat, UserAccount<T>, Flight<BlockNumberFor<T>>, OptionQuery>;
+    pub fn add(&mut self, other: &Cargo) {
+        self.gold = self.gold.saturating_add(other.gold);
+        self.dot = self.dot.saturating_add(other.dot);
+    }
 
-    #[pallet::storage]
-    pub type ActiveShips<T: Config> =
-        StorageMap<_, Twox64Concat, UserAccount<T>, Starship, OptionQuery>;
+    /// Moves up to `space` units out of the cargo, Gold first, and returns them.
+    pub fn take(&mut self, space: u64) -> Cargo {
+        let gold = self.gold.min(space);
+        let dot = self.dot.min(space - gold);
+        self.gold -= gold;
+        self.dot -= dot;
+        Cargo { gold, dot }
+    }
+}
 
-    #[pallet::storage]
-    pub type AccountResources<T: Config> = StorageDoubleMap<
-        _,
-        Twox64Concat,
-        UserAccount<T>,
-        Twox64Concat,
-        AsteroidType,
-        u64, // count
-        ValueQuery,
-    >;
+/// Ship class picked in `start_game`. Each class trades speed, energy and collection abilities.
+#[derive(
+    Encode,
+    Decode,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+    MaxEncodedLen,
+    DecodeWithMemTracking,
+)]
+pub enum ShipClass {
+    /// Balanced ship with the default stats
+    #[default]
+    Standard = 0,
+    /// Fast, but starts with little energy
+    Scout = 1,
+    /// Slow, but sweeps up more asteroids per flight
+    Hauler = 2,
+    /// Collects from further away, but burns more energy
+    Miner = 3,
+}
 
-    #[pallet::storage]
-    pub type DotPrizePool<T> = StorageValue<_, u64, ValueQuery>; // Total amount of DOT deposited into the prize pool
+impl ShipClass {
+    /// Stats used until root overrides them with `admin_set_class_stats`.
+    pub fn default_stats(&self) -> ShipClassStats {
+        let stats = ShipStats::default();
+        match self {
+            ShipClass::Standard => ShipClassStats {
+                energy: DEFAULT_ENERGY,
+                stats,
+            },
+            ShipClass::Scout => ShipClassStats {
+                energy: 70,
+                stats: ShipStats {
+                    flight_duration: 1,
+                    ..stats
+                },
+            },
+            ShipClass::Hauler => ShipClassStats {
+                energy: 120,
+                stats: ShipStats {
+                    flight_duration: 3,
+                    sweep_bonus: 3,
+                    cargo_capacity: 25,
+                    ..stats
+                },
+            },
+            ShipClass::Miner => ShipClassStats {
+                energy: DEFAULT_ENERGY,
+                stats: ShipStats {
+                    collection_radius: 8,
+                    energy_depletion: 3,
+                    ..stats
+                },
+            },
+        }
+    }
+}
 
-    #[pallet::storage]
-    pub type DotEmittedTotal<T> = StorageValue<_, u64, ValueQuery>; // Total amount of DOT already emitted through asteroids
+/// Base characteristics of a ship class, before upgrades and skin perks.
+#[derive(
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    MaxEncodedLen,
+    Clone,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+)]
+pub struct ShipClassStats {
+    /// Energy the ship starts with
+    pub energy: Energy,
+    pub stats: ShipStats,
+}
 
-    #[pallet::storage]
-    pub type LastNftSpawnBlock<T> = StorageValue<_, BlockNumberFor<T>, ValueQuery>; // The block number when the last NFT asteroid was spawned
+/// Ship characteristics fixed when the ship is created in `start_game`.
+#[derive(
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    MaxEncodedLen,
+    Clone,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+)]
+pub struct ShipStats {
+    /// Number of blocks a flight takes
+    pub flight_duration: u32,
+    /// Maximum distance at which the ship can collect an asteroid without flying to it
+    pub collection_radius: u32,
+    /// Energy lost every block
+    pub energy_depletion: u32,
+    /// Extra asteroids collected along the path in sweep mode, on top of `SweepCollectCap`
+    pub sweep_bonus: u32,
+    /// Gold and DOT units the cargo hold fits
+    pub cargo_capacity: u32,
+}
 
-    // Events are crucial because they are the primary way to communicate game state changes to Unity.
-    #[pallet::event]
-    #[pallet::generate_deposit(pub(super) fn deposit_event)]
-    pub enum Event<T: Config> {
-        AsteroidSpawned {
-            resource_id: AsteroidType,
-            coord: Coord,
-        },
+impl Default for ShipStats {
+    fn default() -> Self {
+        Self {
+            flight_duration: FLIGHT_DURATION_BLOCKS,
+            collection_radius: RESOURCE_DISTANCE_LIMIT,
+            energy_depletion: ENERGY_DEPLETION_RATE,
+            sweep_bonus: 0,
+            cargo_capacity: CARGO_CAPACITY,
+        }
+    }
+}
 
-        AsteroidRemoved {
-            coord: Coord,
-        },
-        // This is an important event for synchronizing flights.
-        // When Unity receives it, it approximates the ship's movement between the two points.
-        // ! The code below can be found in the Unity script `ShipEntity`.
-        // ! It is the core logic used for smooth movement.
-        // ! Unity:
-        // ! float elapsed = Time.time - _launchTime;
-        // !  _travelDuration = Mathf.Abs(_blockDifference) * 2f;
-        // ! float time = elapsed / _travelDuration;
-        // ! Vector3.Lerp(_from, _to, time);
-        FlightStarted {
-            owner: T::AccountId,
-            from: Coord,
-            to: Coord,
-            end: BlockNumberFor<T>,
-            nft_skin: u32,
-        },
+/// Gameplay bonuses granted by a ship skin on top of the default `ShipStats`.
+#[derive(
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    Default,
+    MaxEncodedLen,
+    Clone,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+)]
+pub struct SkinPerks {
+    /// Extra energy the ship starts with
+    pub bonus_energy: u32,
+    /// Energy per block subtracted from the depletion rate
+    pub depletion_reduction: u32,
+    /// Extra distance for collecting asteroids without flying to them
+    pub collection_radius_bonus: u32,
+    /// Blocks subtracted from the flight duration
+    pub flight_duration_reduction: u32,
+}
 
-        EnergyDepleted {
-            owner: T::AccountId,
-        },
+#[derive(
+    Encode,
+    Decode,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+    MaxEncodedLen,
+    DecodeWithMemTracking,
+)]
+pub enum SkinRarity {
+    Common = 0,
+    Uncommon = 1,
+    Rare = 2,
+    Mystical = 3,
+}
 
-        GameStarted {
-            owner: T::AccountId,
-            coord: Coord,
-            nft_skin: u32,
-        },
+/// A skin registered in the `Skins` registry.
+#[derive(
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    MaxEncodedLen,
+    Clone,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+)]
+pub struct SkinInfo {
+    /// Resource (usually an NFT) the player must own to use the skin, `None` for free skins
+    pub required: Option<AsteroidKind>,
+    pub rarity: SkinRarity,
+    /// Hash of the display metadata (model, textures) Unity loads for the skin
+    pub metadata_hash: [u8; 32],
+    pub perks: SkinPerks,
+}
 
-        AsteroidCollected {
-            owner: T::AccountId,
-            coord: Coord,
-            resource: AsteroidKind,
-            amount: u32,
-        },
+/// Base of a new chain, in the middle of the main map, until root sets up more.
+pub fn default_base() -> Coord {
+    Coord {
+        x: MAP_SIZE / 2,
+        y: MAP_SIZE / 2,
     }
+}
 
-    #[pallet::error]
-    pub enum Error<T> {
-        StorageOverflow,
-        FlightAlreadyInProgress,
-        NoActiveShip,
-        ShipInFlight,
-        TooFarFromResource,
-        InvalidCoordinates,
-        PlayerAlreadyHasActiveShip,
-        InvalidNftSkin,
-        MissingNftForSkin,
-    }
+/// Skins available on a new chain: the three NFT skins that can be collected from NFT asteroids.
+pub fn default_skins() -> Vec<(u32, SkinInfo)> {
+    Vec::from([
+        (
+            5,
+            SkinInfo {
+                required: Some(AsteroidKind::Nft0),
+                rarity: SkinRarity::Uncommon,
+                metadata_hash: [0; 32],
+                perks: SkinPerks {
+                    bonus_energy: 10,
+                    ..Default::default()
+                },
+            },
+        ),
+        (
+            6,
+            SkinInfo {
+                required: Some(AsteroidKind::Nft1),
+                rarity: SkinRarity::Rare,
+                metadata_hash: [0; 32],
+                perks: SkinPerks {
+                    bonus_energy: 20,
+                    collection_radius_bonus: 1,
+                    ..Default::default()
+                },
+            },
+        ),
+        (
+            7,
+            SkinInfo {
+                required: Some(AsteroidKind::Nft2),
+                rarity: SkinRarity::Mystical,
+                metadata_hash: [0; 32],
+                perks: SkinPerks {
+                    bonus_energy: 30,
+                    depletion_reduction: 1,
+                    collection_radius_bonus: 2,
+                    flight_duration_reduction: 1,
+                },
+            },
+        ),
+    ])
+}
 
-    #[pallet::hooks]
-    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
-        fn on_initialize(now: BlockNumberFor<T>) -> Weight {
-            let mut weight = Weight::zero();
+/// Ship characteristics that can be improved by burning Gold.
+#[derive(
+    Encode,
+    Decode,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+    MaxEncodedLen,
+    DecodeWithMemTracking,
+)]
+pub enum UpgradeKind {
+    MaxEnergy = 0,
+    Speed = 1,
+    CollectionRadius = 2,
+    Efficiency = 3,
+}
 
-            for (user, flight) in Flights::<T>::iter() {
-                if flight.end >= now {
-                    continue;
-                }
+impl UpgradeKind {
+    /// Highest level of the upgrade. Flights and energy depletion cannot go below one per block,
+    /// so speed and efficiency have a single level.
+    pub fn max_level(&self) -> u8 {
+        match self {
+            UpgradeKind::MaxEnergy => 3,
+            UpgradeKind::Speed => 1,
+            UpgradeKind::CollectionRadius => 3,
+            UpgradeKind::Efficiency => 1,
+        }
+    }
+}
 
-                let coord: Coord = flight.to;
+/// Upgrade levels bought by an account. They persist between games.
+#[derive(Encode, Decode, Default, MaxEncodedLen, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct UpgradeLevels {
+    pub max_energy: u8,
+    pub speed: u8,
+    pub collection_radius: u8,
+    pub efficiency: u8,
+}
 
-                weight += Self::collect_asteroid::<T>(user.clone(), coord.clone());
+impl UpgradeLevels {
+    pub fn level(&self, kind: UpgradeKind) -> u8 {
+        match kind {
+            UpgradeKind::MaxEnergy => self.max_energy,
+            UpgradeKind::Speed => self.speed,
+            UpgradeKind::CollectionRadius => self.collection_radius,
+            UpgradeKind::Efficiency => self.efficiency,
+        }
+    }
 
-                if let Some(mut ship) = ActiveShips::<T>::get(&user) {
-                    ship.pos = coord.clone();
-                    ActiveShips::<T>::insert(user.clone(), ship);
-                    weight += T::DbWeight::get().writes(1);
-                }
+    pub fn level_mut(&mut self, kind: UpgradeKind) -> &mut u8 {
+        match kind {
+            UpgradeKind::MaxEnergy => &mut self.max_energy,
+            UpgradeKind::Speed => &mut self.speed,
+            UpgradeKind::CollectionRadius => &mut self.collection_radius,
+            UpgradeKind::Efficiency => &mut self.efficiency,
+        }
+    }
+}
+
+/// How a ship collects asteroids when it lands.
+#[derive(
+    Encode,
+    Decode,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+    MaxEncodedLen,
+    DecodeWithMemTracking,
+)]
+pub enum CollectionMode {
+    /// Only the asteroid at the flight destination is collected
+    #[default]
+    Target = 0,
+    /// Asteroids on the grid line between the start and the destination are collected as well
+    Sweep = 1,
+}
+
+/// A request to collect an asteroid, gathered during a block and resolved in `on_finalize`.
+/// Ships that started their flight earlier win; ties are broken by block randomness.
+#[derive(Encode, Decode, MaxEncodedLen, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct Claim<AccountId, BlockNumber> {
+    pub owner: AccountId,
+    pub ship_id: ShipId,
+    pub flight_start: BlockNumber,
+}
+
+#[derive(
+    Encode,
+    Decode,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+    MaxEncodedLen,
+    DecodeWithMemTracking,
+)]
+pub enum AsteroidKind {
+    Energy = 0,
+    Gold = 1,
+    Dot0 = 2,
+    Dot1 = 3,
+    Dot2 = 4,
+    Nft0 = 5,  //Uncommon
+    Nft1 = 6,  //Rare
+    Nft2 = 7,  //Mystical
+    Wreck = 8, // Cargo left behind by a ship that ran out of energy
+    Shield = 9,
+    Booster = 10,
+    Magnet = 11,
+    Radiation = 12, // Drains energy instead of giving a reward
+}
+
+/// Area of the map defined by root in which ships lose extra energy every block.
+#[derive(
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    MaxEncodedLen,
+    Clone,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+)]
+pub struct HazardZone {
+    /// Corner of the zone with the lowest coordinates
+    pub min: Coord,
+    /// Corner of the zone with the highest coordinates, inclusive
+    pub max: Coord,
+    /// Energy lost every block on top of the ship's own depletion
+    pub extra_depletion: u32,
+}
+
+impl HazardZone {
+    pub fn contains(&self, coord: &Coord) -> bool {
+        (self.min.x..=self.max.x).contains(&coord.x) && (self.min.y..=self.max.y).contains(&coord.y)
+    }
+}
+
+/// Rectangular area of the map asteroids spawn in.
+#[derive(
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    MaxEncodedLen,
+    Clone,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+)]
+pub struct SpawnZone {
+    /// Corner of the zone with the lowest coordinates
+    pub min: Coord,
+    /// Corner of the zone with the highest coordinates, inclusive
+    pub max: Coord,
+    /// Relative chance of the zone being picked for a spawn
+    pub weight: u32,
+    /// Relative chances of the asteroid kinds spawned in the zone. Empty uses the default spawn table.
+    pub asteroid_weights: BoundedVec<(AsteroidKind, u32), ConstU32<MAX_ZONE_ASTEROID_KINDS>>,
+}
+
+/// Map uploaded by root with `admin_upload_map`, replacing the map size, blocked tiles, spawn zones and bases.
+#[derive(
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    MaxEncodedLen,
+    Clone,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+)]
+pub struct MapLayout {
+    pub size: u32,
+    /// Rows of the blocked tiles bitmap by `y`, in the format of `BlockedRows`
+    pub blocked_rows:
+        BoundedVec<(u32, BoundedVec<u8, ConstU32<MAX_MAP_ROW_BYTES>>), ConstU32<MAX_MAP_ROWS>>,
+    /// Areas asteroids spawn in. Without zones asteroids spawn anywhere on the map.
+    pub spawn_zones: BoundedVec<SpawnZone, ConstU32<MAX_SPAWN_ZONES>>,
+    pub base_tiles: BoundedVec<Coord, ConstU32<MAX_BASE_TILES>>,
+}
+
+/// Rules of an arena, picked by its creator in `create_arena`.
+#[derive(
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    MaxEncodedLen,
+    Clone,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+)]
+pub struct ArenaConfig {
+    pub map_size: u32,
+    /// Number of asteroids the arena is kept filled up to
+    pub max_asteroids: u32,
+    /// Number of accounts that can have ships in the arena at the same time
+    pub max_players: u32,
+    pub topology: MapTopology,
+}
+
+/// A game running next to the shared map. An arena has no blocked tiles or hazards,
+/// its only base is the tile in the middle of the map.
+#[derive(
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    MaxEncodedLen,
+    Clone,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+)]
+pub struct Arena<AccountId> {
+    pub creator: AccountId,
+    pub config: ArenaConfig,
+}
+
+/// Match mode picked in `join_queue`. A match starts in a fresh arena once enough players are waiting.
+#[derive(
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    MaxEncodedLen,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+)]
+pub enum MatchMode {
+    /// Two players on a small map
+    #[default]
+    Quick,
+    /// Four players on a larger map
+    Squad,
+    /// Eight players in a shrinking safe zone, the last ship standing takes the prize pool
+    BattleRoyale,
+}
+
+impl MatchMode {
+    pub const ALL: [MatchMode; 3] = [MatchMode::Quick, MatchMode::Squad, MatchMode::BattleRoyale];
+
+    /// Number of queued players a match of this mode starts with.
+    pub fn players(&self) -> u32 {
+        match self {
+            MatchMode::Quick => 2,
+            MatchMode::Squad => 4,
+            MatchMode::BattleRoyale => MAX_MATCH_PLAYERS,
+        }
+    }
+
+    /// Rules of the arena a match of this mode is played in.
+    pub fn arena_config(&self) -> ArenaConfig {
+        match self {
+            MatchMode::Quick => ArenaConfig {
+                map_size: 16,
+                max_asteroids: 10,
+                max_players: self.players(),
+                topology: MapTopology::Bounded,
+            },
+            MatchMode::Squad => ArenaConfig {
+                map_size: 24,
+                max_asteroids: 20,
+                max_players: self.players(),
+                topology: MapTopology::Bounded,
+            },
+            MatchMode::BattleRoyale => ArenaConfig {
+                map_size: 32,
+                max_asteroids: 30,
+                max_players: self.players(),
+                topology: MapTopology::Bounded,
+            },
+        }
+    }
+}
+
+/// Playable area of a battle royale arena. Tiles further than `radius` steps from `center` are outside.
+#[derive(
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    MaxEncodedLen,
+    Clone,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+)]
+pub struct SafeZone<BlockNumber> {
+    pub center: Coord,
+    pub radius: u32,
+    /// Block at which the radius shrinks next
+    pub next_shrink: BlockNumber,
+}
+
+impl<BlockNumber> SafeZone<BlockNumber> {
+    pub fn contains(&self, coord: &Coord) -> bool {
+        get_distance(self.center.clone(), coord.clone(), None) <= self.radius
+    }
+}
+
+/// Match started from the queue. Only its players can join the arena, which closes when the match ends.
+#[derive(
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    MaxEncodedLen,
+    Clone,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+)]
+pub struct Match<AccountId, BlockNumber> {
+    pub mode: MatchMode,
+    pub players: BoundedVec<AccountId, ConstU32<MAX_MATCH_PLAYERS>>,
+    /// Block at which the match ends unless every ship is gone before
+    pub end: BlockNumber,
+}
+
+/// Time-boxed match played in its own arena. Registered accounts pay the entry fee in DOT into the pot,
+/// which is paid out by the Gold collected during the match once it ends.
+#[derive(
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    MaxEncodedLen,
+    Clone,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+)]
+pub struct Tournament<AccountId, BlockNumber> {
+    pub organizer: AccountId,
+    pub entry_fee: u64,
+    /// Share of the pot paid to each place, best place first
+    pub payout: BoundedVec<Percent, ConstU32<MAX_PAYOUT_PLACES>>,
+    /// First block of the match, registration closes before it
+    pub start: BlockNumber,
+    /// Block at which the match ends and the pot is paid out
+    pub end: BlockNumber,
+    /// Entry fees paid so far
+    pub pot: u64,
+    /// Registered accounts in registration order, which breaks ties in the ranking
+    pub players: BoundedVec<AccountId, ConstU32<MAX_TOURNAMENT_PLAYERS>>,
+    pub started: bool,
+}
+
+/// Duel offered with `challenge`, waiting for the opponent to accept it.
+/// The wager of the challenger is escrowed until then.
+#[derive(
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    MaxEncodedLen,
+    Clone,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+)]
+pub struct Challenge<AccountId, BlockNumber> {
+    pub challenger: AccountId,
+    pub opponent: AccountId,
+    /// DOT put in by each side
+    pub wager: u64,
+    /// Length of the duel in blocks once accepted
+    pub duration: u32,
+    /// Block at which the challenge expires unless accepted
+    pub expires: BlockNumber,
+}
+
+/// Private two-player match. The account with more Gold collected in the duel takes both wagers.
+#[derive(
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    MaxEncodedLen,
+    Clone,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+)]
+pub struct Duel<AccountId, BlockNumber> {
+    pub challenger: AccountId,
+    pub opponent: AccountId,
+    pub wager: u64,
+    pub end: BlockNumber,
+}
+
+/// Region of the main map owned by the account that staked the most Gold on it.
+#[derive(
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    MaxEncodedLen,
+    Clone,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+)]
+pub struct Territory<AccountId> {
+    pub owner: AccountId,
+    /// Gold locked by the owner, refunded when another account takes the region over
+    pub stake: u64,
+}
+
+/// What a station produces, picked in `build_station`.
+#[derive(
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    MaxEncodedLen,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+)]
+pub enum StationOutput {
+    /// Gold banked straight to the owner's account
+    #[default]
+    Gold,
+    /// Energy stored in the station until a ship of the owner docks on it
+    Energy,
+}
+
+/// Station built by a player on the main map. Every station recharges the ships of its owner docked on its tile.
+#[derive(
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    MaxEncodedLen,
+    Clone,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+)]
+pub struct Station<AccountId, BlockNumber> {
+    pub owner: AccountId,
+    pub output: StationOutput,
+    /// Starts at 1, every level multiplies the yield and the recharge
+    pub level: u8,
+    /// Energy produced and not yet handed to a docked ship
+    pub stored_energy: u32,
+    pub next_yield: BlockNumber,
+}
+
+/// Shape of the map. A ship flying past the edge of a toroidal map comes back on the other side.
+#[derive(
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    MaxEncodedLen,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+    RuntimeDebug,
+    TypeInfo,
+)]
+pub enum MapTopology {
+    #[default]
+    Bounded,
+    Toroidal,
+}
+
+/// Sent with flights so Unity can lerp across the edge of a toroidal map: the number of map
+/// widths to add to `to` on each axis before the lerp, `-1`, `0` or `1`. Always zero on a bounded map.
+#[derive(
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    MaxEncodedLen,
+    Clone,
+    PartialEq,
+    Eq,
+    Default,
+    RuntimeDebug,
+    TypeInfo,
+)]
+pub struct MapWrap {
+    pub x: i8,
+    pub y: i8,
+}
+
+/// Part of the game a map cleanup is going through.
+#[derive(
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    MaxEncodedLen,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+)]
+pub enum MapCleanupStage {
+    /// Ships outside of the map are moved back in, flights and routes leaving it are cancelled
+    Ships,
+    /// Asteroids outside of the map are removed
+    Asteroids,
+    /// Territories outside of the map are released and their stake refunded
+    Territories,
+    /// Stations outside of the map are removed
+    Stations,
+}
+
+/// Progress of moving ships, asteroids, territories and stations a shrunk map left outside of its bounds,
+/// done over several blocks in batches of `MAP_CLEANUP_BATCH`.
+#[derive(
+    Encode,
+    Decode,
+    DecodeWithMemTracking,
+    MaxEncodedLen,
+    Clone,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+)]
+pub struct MapCleanup {
+    pub size: u32,
+    pub stage: MapCleanupStage,
+    /// Raw storage key of the last entry checked, `None` at the start of a stage
+    pub cursor: Option<BoundedVec<u8, ConstU32<MAX_CLEANUP_CURSOR_LEN>>>,
+}
+
+/// Everything Unity needs to draw the map, returned by the `get_world_snapshot` view function.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct WorldSnapshot<BlockNumber> {
+    pub map_size: u32,
+    pub topology: MapTopology,
+    /// Asteroids with the block at which they disappear
+    pub asteroids: Vec<(Coord, AsteroidKind, BlockNumber)>,
+    pub base_tiles: Vec<Coord>,
+    pub black_holes: Vec<Coord>,
+    pub hazard_zones: Vec<HazardZone>,
+    /// Rows of the blocked tiles bitmap by `y`, bit `x % 8` of byte `x / 8` is set for a blocked tile
+    pub blocked_rows: Vec<(u32, Vec<u8>)>,
+    pub spawn_zones: Vec<SpawnZone>,
+}
+
+impl AsteroidKind {
+    /// Whether the asteroid is loaded into the cargo hold instead of going straight to the account.
+    pub fn is_cargo(&self) -> bool {
+        matches!(
+            self,
+            AsteroidKind::Gold
+                | AsteroidKind::Dot0
+                | AsteroidKind::Dot1
+                | AsteroidKind::Dot2
+                | AsteroidKind::Wreck
+        )
+    }
+
+    /// The power-up granted by collecting the asteroid, if it is a power-up.
+    pub fn power_up(&self) -> Option<PowerUp> {
+        match self {
+            AsteroidKind::Shield => Some(PowerUp::Shield),
+            AsteroidKind::Booster => Some(PowerUp::Booster),
+            AsteroidKind::Magnet => Some(PowerUp::Magnet),
+            _ => None,
+        }
+    }
+}
+
+pub type AsteroidType = AsteroidKind;
+pub type Energy = u32;
+/// Index of a ship within the fleet of an account, below `MAX_FLEET_SIZE`
+pub type ShipId = u32;
+/// Id of a game with its own map, asteroids and prize pool. `MAIN_GAME` is the shared map, the rest are arenas.
+pub type GameId = u32;
+
+#[frame_support::pallet]
+pub mod pallet {
+
+    use super::*;
+    use frame_support::{
+        pallet_prelude::*,
+        runtime_print,
+        sp_runtime::{Percent, SaturatedConversion, Saturating},
+    };
+    use frame_system::pallet_prelude::*;
+
+    /// Version 1 keys the game state by game and the ships and flights by fleet slot.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+    #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
+    pub struct Pallet<T>(_);
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+        type WeightInfo: WeightInfo;
+    }
+
+    type UserAccount<T> = <T as frame_system::Config>::AccountId;
+    pub type StationOf<T> = Station<UserAccount<T>, BlockNumberFor<T>>;
+
+    #[pallet::storage]
+    pub type MapSize<T> = StorageValue<_, u32>;
+
+    #[pallet::storage]
+    pub type MaxAsteroidsCount<T> = StorageValue<_, u32>;
+
+    // Number of accounts with at least one active ship in a game.
+    #[pallet::storage]
+    pub type PlayersCount<T> = StorageMap<_, Twox64Concat, GameId, u32, ValueQuery>;
+
+    // Open arenas. The main game has no entry, its rules are the root-configured map settings.
+    #[pallet::storage]
+    pub type Arenas<T: Config> =
+        StorageMap<_, Twox64Concat, GameId, Arena<UserAccount<T>>, OptionQuery>;
+
+    // Tournaments keyed by the arena they are played in.
+    #[pallet::storage]
+    pub type Tournaments<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        GameId,
+        Tournament<UserAccount<T>, BlockNumberFor<T>>,
+        OptionQuery,
+    >;
+
+    // Gold collected by each account during a tournament or a duel.
+    #[pallet::storage]
+    pub type MatchScores<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, GameId, Twox64Concat, UserAccount<T>, u64, ValueQuery>;
+
+    // Challenges waiting to be accepted.
+    #[pallet::storage]
+    pub type Challenges<T: Config> =
+        StorageMap<_, Twox64Concat, u32, Challenge<UserAccount<T>, BlockNumberFor<T>>, OptionQuery>;
+
+    #[pallet::storage]
+    pub type NextChallengeId<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    // Challenges keyed by the block they expire at, so only the due ones are looked up.
+    #[pallet::storage]
+    pub type ChallengeExpiries<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        BlockNumberFor<T>,
+        BoundedVec<u32, ConstU32<MAX_CHALLENGES_PER_BLOCK>>,
+        ValueQuery,
+    >;
+
+    // Duels keyed by the arena they are played in.
+    #[pallet::storage]
+    pub type Duels<T: Config> =
+        StorageMap<_, Twox64Concat, GameId, Duel<UserAccount<T>, BlockNumberFor<T>>, OptionQuery>;
+
+    // Safe zones of the running battle royale matches.
+    #[pallet::storage]
+    pub type SafeZones<T: Config> =
+        StorageMap<_, Twox64Concat, GameId, SafeZone<BlockNumberFor<T>>, OptionQuery>;
+
+    // Running matches started from the queue, keyed by their arena.
+    #[pallet::storage]
+    pub type Matches<T: Config> =
+        StorageMap<_, Twox64Concat, GameId, Match<UserAccount<T>, BlockNumberFor<T>>, OptionQuery>;
+
+    // Accounts waiting for a match of each mode, in the order they joined.
+    #[pallet::storage]
+    pub type MatchQueue<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        MatchMode,
+        BoundedVec<UserAccount<T>, ConstU32<MAX_QUEUE_LEN>>,
+        ValueQuery,
+    >;
+
+    // Id of the next arena, arenas are numbered from 1.
+    #[pallet::storage]
+    pub type NextArenaId<T> = StorageValue<_, GameId, ValueQuery>;
+
+    // Maximum number of asteroids a sweeping ship collects along its path (the destination is not counted).
+    #[pallet::storage]
+    pub type SweepCollectCap<T> = StorageValue<_, u32>;
+
+    // Owned regions of the main map keyed by their tile with the lowest coordinates.
+    #[pallet::storage]
+    pub type Territories<T: Config> =
+        StorageMap<_, Twox64Concat, Coord, Territory<UserAccount<T>>, OptionQuery>;
+
+    // Share of the Gold collected inside someone else's region that is paid to its owner.
+    #[pallet::storage]
+    pub type TerritoryTax<T> = StorageValue<_, Percent>;
+
+    // Stations of the main map keyed by their tile.
+    #[pallet::storage]
+    pub type Stations<T: Config> = StorageMap<_, Twox64Concat, Coord, StationOf<T>, OptionQuery>;
+
+    // Number of stations owned by each account.
+    #[pallet::storage]
+    pub type StationsCount<T: Config> =
+        StorageMap<_, Twox64Concat, UserAccount<T>, u32, ValueQuery>;
+
+    // Storage key of the last station visited by `on_initialize`, the next batch starts after it.
+    #[pallet::storage]
+    pub type StationCursor<T> =
+        StorageValue<_, BoundedVec<u8, ConstU32<MAX_CLEANUP_CURSOR_LEN>>, OptionQuery>;
+
+    // Share of its cargo a ship leaves in its wreck when it runs out of energy, the rest is lost.
+    #[pallet::storage]
+    pub type WreckShare<T> = StorageValue<_, Percent>;
+
+    // Number of blocks a wreck can be looted before it disappears.
+    #[pallet::storage]
+    pub type WreckTtl<T> = StorageValue<_, u32>;
+
+    // Tiles ships cannot fly through and asteroids do not spawn on (asteroid belts, planets).
+    // Stored as a bitmap per row `y`: bit `x % 8` of byte `x / 8` is set for a blocked tile.
+    // Rows without blocked tiles are not stored.
+    #[pallet::storage]
+    pub type BlockedRows<T> =
+        StorageMap<_, Twox64Concat, u32, BoundedVec<u8, ConstU32<MAX_MAP_ROW_BYTES>>, OptionQuery>;
+
+    #[pallet::storage]
+    pub type Topology<T> = StorageValue<_, MapTopology, ValueQuery>;
+
+    // Cleanup of a shrunk map still in progress.
+    #[pallet::storage]
+    pub type MapCleanupProgress<T> = StorageValue<_, MapCleanup, OptionQuery>;
+
+    // Areas of the map asteroids spawn in, set by `admin_upload_map`. Empty means the whole map.
+    #[pallet::storage]
+    pub type SpawnZones<T> =
+        StorageValue<_, BoundedVec<SpawnZone, ConstU32<MAX_SPAWN_ZONES>>, ValueQuery>;
+
+    // Tiles that pull landing ships towards them.
+    #[pallet::storage]
+    pub type BlackHoles<T> =
+        StorageValue<_, BoundedVec<Coord, ConstU32<MAX_BLACK_HOLES>>, ValueQuery>;
+
+    #[pallet::storage]
+    pub type HazardZones<T> =
+        StorageValue<_, BoundedVec<HazardZone, ConstU32<MAX_HAZARD_ZONES>>, ValueQuery>;
+
+    // Number of blocks an account waits before it can start a new ship after losing one.
+    #[pallet::storage]
+    pub type RespawnCooldownBlocks<T> = StorageValue<_, u32>;
+
+    // First block at which an account that lost a ship can call `start_game` again.
+    #[pallet::storage]
+    pub type RespawnAt<T: Config> =
+        StorageMap<_, Twox64Concat, UserAccount<T>, BlockNumberFor<T>, OptionQuery>;
+
+    // Number of ships an account can lose per season. Lives are unlimited when not set.
+    #[pallet::storage]
+    pub type LivesPerSeason<T> = StorageValue<_, u32>;
+
+    #[pallet::storage]
+    pub type Season<T> = StorageValue<_, u32, ValueQuery>;
+
+    // Ships lost by an account as (season, count). The count only applies while the season is current.
+    #[pallet::storage]
+    pub type LostShips<T: Config> =
+        StorageMap<_, Twox64Concat, UserAccount<T>, (u32, u32), ValueQuery>;
+
+    // #[pallet::storage]
+    //  pub type AsteroidIds<T> = StorageValue<_, u64, ValueQuery>;
+
+    #[pallet::storage]
+    pub type Asteroids<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        GameId,
+        Twox64Concat,
+        Coord,
+        (AsteroidType, BlockNumberFor<T>),
+        OptionQuery,
+    >;
+
+    // Contents of the `Wreck` asteroids lying on the map.
+    #[pallet::storage]
+    pub type Wrecks<T> =
+        StorageDoubleMap<_, Twox64Concat, GameId, Twox64Concat, Coord, Cargo, OptionQuery>;
+
+    // Tiles of the main game where ships can deposit their cargo with `deposit_cargo`.
+    #[pallet::storage]
+    pub type BaseTiles<T> = StorageMap<_, Twox64Concat, Coord, (), OptionQuery>;
+
+    #[pallet::storage]
+    pub type Flights<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        UserAccount<T>,
+        Twox64Concat,
+        ShipId,
+        Flight<BlockNumberFor<T>>,
+        OptionQuery,
+    >;
+
+    // Waypoints still ahead of the current flight of a planned route.
+    // An empty list means the ship is flying to the last waypoint.
+    #[pallet::storage]
+    pub type Routes<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        UserAccount<T>,
+        Twox64Concat,
+        ShipId,
+        BoundedVec<Coord, ConstU32<MAX_ROUTE_WAYPOINTS>>,
+        OptionQuery,
+    >;
+
+    // Ships on the map, keyed by owner and ship id. An account flies up to `MAX_FLEET_SIZE` ships.
+    #[pallet::storage]
+    pub type ActiveShips<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        UserAccount<T>,
+        Twox64Concat,
+        ShipId,
+        Starship<BlockNumberFor<T>>,
+        OptionQuery,
+    >;
+
+    #[pallet::storage]
+    pub type AccountResources<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        UserAccount<T>,
+        Twox64Concat,
+        AsteroidType,
+        u64, // count
+        ValueQuery,
+    >;
+
+    // Skins players can pick in `start_game`, keyed by skin id. Skin 0 is the default ship and is always available.
+    #[pallet::storage]
+    pub type Skins<T> = StorageMap<_, Twox64Concat, u32, SkinInfo, OptionQuery>;
+
+    #[pallet::genesis_config]
+    #[derive(frame_support::DefaultNoBound)]
+    pub struct GenesisConfig<T: Config> {
+        #[serde(skip)]
+        pub _config: core::marker::PhantomData<T>,
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+        fn build(&self) {
+            for (skin_id, skin) in default_skins() {
+                Skins::<T>::insert(skin_id, skin);
+            }
+
+            // A single base in the middle of the map until root sets up more
+            BaseTiles::<T>::insert(default_base(), ());
+        }
+    }
+
+    // Root overrides of the ship class stats. Classes without an entry use `ShipClass::default_stats`.
+    #[pallet::storage]
+    pub type ClassStats<T> = StorageMap<_, Twox64Concat, ShipClass, ShipClassStats, OptionQuery>;
+
+    #[pallet::storage]
+    pub type ShipUpgrades<T: Config> =
+        StorageMap<_, Twox64Concat, UserAccount<T>, UpgradeLevels, ValueQuery>;
+
+    #[pallet::storage]
+    pub type DotPrizePool<T> = StorageMap<_, Twox64Concat, GameId, u64, ValueQuery>; // Total amount of DOT deposited into the prize pool of a game
+
+    #[pallet::storage]
+    pub type DotEmittedTotal<T> = StorageMap<_, Twox64Concat, GameId, u64, ValueQuery>; // Total amount of DOT already emitted through asteroids of a game
+
+    #[pallet::storage]
+    pub type LastNftSpawnBlock<T> =
+        StorageMap<_, Twox64Concat, GameId, BlockNumberFor<T>, ValueQuery>; // The block number when the last NFT asteroid of a game was spawned
+
+    // Claims for asteroids gathered during the current block (landings and collect calls).
+    // They are resolved together in `on_finalize`, so the order of ships inside a block does not matter.
+    #[pallet::storage]
+    pub type PendingClaims<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        GameId,
+        Twox64Concat,
+        Coord,
+        BoundedVec<Claim<UserAccount<T>, BlockNumberFor<T>>, ConstU32<MAX_CLAIMANTS_PER_ASTEROID>>,
+        ValueQuery,
+    >;
+
+    // Events are crucial because they are the primary way to communicate game state changes to Unity.
+    // Events about a tile carry the game it belongs to, events about a ship go by the game of the ship.
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        AsteroidSpawned {
+            game: GameId,
+            resource_id: AsteroidType,
+            coord: Coord,
+        },
+
+        AsteroidRemoved {
+            game: GameId,
+            coord: Coord,
+        },
+        // This is an important event for synchronizing flights.
+        // When Unity receives it, it approximates the ship's movement between the two points.
+        // ! The code below can be found in the Unity script `ShipEntity`.
+        // ! It is the core logic used for smooth movement.
+        // ! Unity:
+        // ! float elapsed = Time.time - _launchTime;
+        // !  _travelDuration = Mathf.Abs(_blockDifference) * 2f;
+        // ! float time = elapsed / _travelDuration;
+        // ! Vector3.Lerp(_from, _to, time);
+        FlightStarted {
+            owner: T::AccountId,
+            ship_id: ShipId,
+            from: Coord,
+            to: Coord,
+            end: BlockNumberFor<T>,
+            nft_skin: u32,
+            wrap: MapWrap,
+        },
+
+        // Sent instead of `FlightStarted` when a ship changes course mid-flight.
+        // `from` is the interpolated position of the ship at the current block,
+        // so Unity can restart the lerp from there.
+        FlightRedirected {
+            owner: T::AccountId,
+            ship_id: ShipId,
+            from: Coord,
+            to: Coord,
+            end: BlockNumberFor<T>,
+            nft_skin: u32,
+            wrap: MapWrap,
+        },
+
+        // Emitted every time a ship following a planned route lands on one of its waypoints.
+        WaypointReached {
+            owner: T::AccountId,
+            ship_id: ShipId,
+            coord: Coord,
+            remaining: u32,
+        },
+
+        // The ship did not have enough energy for the next leg, the rest of the route is dropped.
+        RouteAborted {
+            owner: T::AccountId,
+            ship_id: ShipId,
+            coord: Coord,
+        },
+
+        CollectionModeChanged {
+            owner: T::AccountId,
+            ship_id: ShipId,
+            mode: CollectionMode,
+        },
+
+        SkinRegistered {
+            skin_id: u32,
+        },
+
+        SkinRemoved {
+            skin_id: u32,
+        },
+
+        ClassStatsUpdated {
+            class: ShipClass,
+            stats: ShipClassStats,
+        },
+
+        ShipUpgraded {
+            owner: T::AccountId,
+            kind: UpgradeKind,
+            level: u8,
+            cost: u64,
+        },
+
+        EnergyDepleted {
+            owner: T::AccountId,
+            ship_id: ShipId,
+        },
+
+        GameStarted {
+            game: GameId,
+            owner: T::AccountId,
+            ship_id: ShipId,
+            coord: Coord,
+            nft_skin: u32,
+            class: ShipClass,
+        },
+
+        AsteroidCollected {
+            game: GameId,
+            owner: T::AccountId,
+            ship_id: ShipId,
+            coord: Coord,
+            resource: AsteroidKind,
+            amount: u32,
+        },
+
+        CargoDeposited {
+            owner: T::AccountId,
+            ship_id: ShipId,
+            gold: u64,
+            dot: u64,
+        },
+
+        // A ship ran out of energy with a loaded cargo hold. The cargo can be looted from the `Wreck` asteroid at `coord`.
+        WreckDropped {
+            game: GameId,
+            owner: T::AccountId,
+            ship_id: ShipId,
+            coord: Coord,
+            cargo: Cargo,
+        },
+
+        BaseTileSet {
+            coord: Coord,
+            enabled: bool,
+        },
+
+        // The ship landed next to a black hole and was pulled from `from` to `to`.
+        ShipPulled {
+            owner: T::AccountId,
+            ship_id: ShipId,
+            from: Coord,
+            to: Coord,
+        },
+
+        PowerUpActivated {
+            owner: T::AccountId,
+            ship_id: ShipId,
+            power_up: PowerUp,
+            until: BlockNumberFor<T>,
+        },
+
+        PowerUpExpired {
+            owner: T::AccountId,
+            ship_id: ShipId,
+            power_up: PowerUp,
+        },
+
+        MapLayoutUploaded {
+            size: u32,
+        },
+
+        // The map was resized. A shrunk map is cleaned up over the next blocks.
+        MapSizeChanged {
+            size: u32,
+        },
+
+        // The ship was outside of a shrunk map, or flying out of it, and was moved to `to`.
+        // Its flight and route are cancelled.
+        ShipRelocated {
+            owner: T::AccountId,
+            ship_id: ShipId,
+            from: Coord,
+            to: Coord,
+        },
+
+        // No ship or asteroid is left outside of the map.
+        MapCleanupFinished {
+            size: u32,
+        },
+
+        ArenaCreated {
+            game: GameId,
+            creator: T::AccountId,
+            config: ArenaConfig,
+        },
+
+        ArenaClosed {
+            game: GameId,
+        },
+
+        QueueJoined {
+            mode: MatchMode,
+            who: T::AccountId,
+        },
+
+        QueueLeft {
+            mode: MatchMode,
+            who: T::AccountId,
+        },
+
+        // A fresh arena was opened for queued players. Players whose ship could not enter are left out.
+        MatchStarted {
+            game: GameId,
+            mode: MatchMode,
+            participants: BoundedVec<T::AccountId, ConstU32<MAX_MATCH_PLAYERS>>,
+        },
+
+        // The match ran out of time or every ship in it is gone. The ships left banked their cargo.
+        MatchFinished {
+            game: GameId,
+        },
+
+        ChallengeIssued {
+            challenge_id: u32,
+            challenger: T::AccountId,
+            opponent: T::AccountId,
+            wager: u64,
+            expires: BlockNumberFor<T>,
+        },
+
+        // The challenge was not accepted in time, the wager went back to the challenger.
+        ChallengeExpired {
+            challenge_id: u32,
+        },
+
+        DuelStarted {
+            game: GameId,
+            challenge_id: u32,
+            challenger: T::AccountId,
+            opponent: T::AccountId,
+            end: BlockNumberFor<T>,
+        },
+
+        // `winner` took the whole pot. A tie refunds both wagers and has no winner.
+        DuelFinished {
+            game: GameId,
+            winner: Option<T::AccountId>,
+            pot: u64,
+        },
+
+        SafeZoneShrunk {
+            game: GameId,
+            center: Coord,
+            radius: u32,
+        },
+
+        // The last account with ships left took the part of the arena prize pool not yet emitted as a bonus.
+        // Without a winner every ship ran out of energy in the same block.
+        BattleRoyaleFinished {
+            game: GameId,
+            winner: Option<T::AccountId>,
+            bonus: u64,
+        },
+
+        // `region` is the tile of the region with the lowest coordinates. A previous owner got their stake back.
+        TerritoryClaimed {
+            region: Coord,
+            owner: T::AccountId,
+            stake: u64,
+            previous_owner: Option<T::AccountId>,
+        },
+
+        // The region is past the edge of a shrunk map, the stake went back to the owner.
+        TerritoryReleased {
+            region: Coord,
+            owner: T::AccountId,
+        },
+
+        TerritoryTaxPaid {
+            region: Coord,
+            owner: T::AccountId,
+            payer: T::AccountId,
+            gold: u64,
+        },
+
+        StationBuilt {
+            owner: T::AccountId,
+            coord: Coord,
+            output: StationOutput,
+        },
+
+        StationUpgraded {
+            coord: Coord,
+            level: u8,
+        },
+
+        // Gold banked to the owner and energy handed to the owner's docked ships.
+        StationYielded {
+            coord: Coord,
+            owner: T::AccountId,
+            gold: u64,
+            energy: u32,
+        },
+
+        // The station is past the edge of a shrunk map.
+        StationRemoved {
+            coord: Coord,
+            owner: T::AccountId,
+        },
+
+        TournamentCreated {
+            game: GameId,
+            organizer: T::AccountId,
+            entry_fee: u64,
+            start: BlockNumberFor<T>,
+            end: BlockNumberFor<T>,
+        },
+
+        TournamentRegistered {
+            game: GameId,
+            who: T::AccountId,
+        },
+
+        // Registered accounts can join the arena of the tournament from now on.
+        TournamentStarted {
+            game: GameId,
+        },
+
+        // The pot was paid out as DOT, best place first. The ships left the arena with their cargo banked.
+        TournamentFinished {
+            game: GameId,
+            payouts: BoundedVec<(T::AccountId, u64), ConstU32<MAX_PAYOUT_PLACES>>,
+        },
+
+        // Lives of every account are restored.
+        SeasonStarted {
+            season: u32,
+        },
+
+        // Emitted when several ships claimed the same asteroid in one block.
+        AsteroidContested {
+            game: GameId,
+            coord: Coord,
+            contenders: BoundedVec<(T::AccountId, ShipId), ConstU32<MAX_CLAIMANTS_PER_ASTEROID>>,
+            winner: T::AccountId,
+            ship_id: ShipId,
+        },
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        StorageOverflow,
+        FlightAlreadyInProgress,
+        NoActiveShip,
+        ShipInFlight,
+        TooFarFromResource,
+        InvalidCoordinates,
+        PlayerAlreadyHasActiveShip,
+        InvalidNftSkin,
+        MissingNftForSkin,
+        TooManyClaimants,
+        NoFlightInProgress,
+        NotEnoughEnergy,
+        EmptyRoute,
+        UpgradeMaxed,
+        NotEnoughGold,
+        InvalidClassStats,
+        CargoFull,
+        CargoEmpty,
+        NotAtBase,
+        InvalidWreckTtl,
+        RespawnCooldown,
+        NoLivesLeft,
+        InvalidHazardZone,
+        PathBlocked,
+        TileBlocked,
+        InvalidMapLayout,
+        InvalidMapSize,
+        InvalidArenaConfig,
+        TooManyArenas,
+        ArenaNotFound,
+        ArenaFull,
+        NotArenaCreator,
+        ArenaNotEmpty,
+        InvalidTournament,
+        TournamentNotFound,
+        RegistrationClosed,
+        AlreadyRegistered,
+        TournamentFull,
+        NotEnoughDot,
+        NotRegistered,
+        TournamentNotStarted,
+        ArenaInTournament,
+        AlreadyQueued,
+        NotQueued,
+        QueueFull,
+        InvalidChallenge,
+        ChallengeNotFound,
+        NotChallengeOpponent,
+        ArenaInDuel,
+        TerritoryStakeTooLow,
+        AlreadyTerritoryOwner,
+        TooManyStations,
+        StationExists,
+        StationNotFound,
+        NotStationOwner,
+        ArenaInMatch,
+        TooManyChallenges,
+        FleetFull,
+        MatchAlreadyStarted,
+    }
+
+    // Read-only queries for clients (Unity), served through the `RuntimeViewFunction` runtime API.
+    #[pallet::view_functions_experimental]
+    impl<T: Config> Pallet<T> {
+        /// Gameplay perks of a ship skin, `None` if the skin does not exist.
+        pub fn get_skin_perks(nft_skin: u32) -> Option<SkinPerks> {
+            Self::skin_perks(nft_skin)
+        }
+
+        /// Base stats of a ship class.
+        pub fn get_class_stats(class: ShipClass) -> ShipClassStats {
+            Self::class_stats(class)
+        }
+
+        /// A skin from the registry, including its rarity and display metadata hash.
+        pub fn get_skin(skin_id: u32) -> Option<SkinInfo> {
+            Skins::<T>::get(skin_id)
+        }
+
+        /// Every active ship of an account together with its ship id.
+        pub fn get_fleet(who: T::AccountId) -> Vec<(ShipId, Starship<BlockNumberFor<T>>)> {
+            ActiveShips::<T>::iter_prefix(who).collect()
+        }
+
+        /// Asteroids, bases and hazards currently on the map of the main game.
+        pub fn get_world_snapshot() -> WorldSnapshot<BlockNumberFor<T>> {
+            Self::world_snapshot(MAIN_GAME)
+        }
+
+        /// Rules and creator of an open arena.
+        pub fn get_arena(game: GameId) -> Option<Arena<T::AccountId>> {
+            Arenas::<T>::get(game)
+        }
+
+        /// Stations of the main map by their tile.
+        pub fn get_stations() -> Vec<(Coord, StationOf<T>)> {
+            Stations::<T>::iter().collect()
+        }
+
+        /// Owned regions of the main map by their tile with the lowest coordinates. A region is 8 x 8 tiles.
+        pub fn get_territories() -> Vec<(Coord, Territory<T::AccountId>)> {
+            Territories::<T>::iter().collect()
+        }
+
+        /// Safe zone of a battle royale arena, `None` for other games.
+        pub fn get_safe_zone(game: GameId) -> Option<SafeZone<BlockNumberFor<T>>> {
+            SafeZones::<T>::get(game)
+        }
+
+        /// Asteroids and bases currently on the map of an arena, `None` if the arena is not open.
+        pub fn get_arena_snapshot(game: GameId) -> Option<WorldSnapshot<BlockNumberFor<T>>> {
+            Arenas::<T>::contains_key(game).then(|| Self::world_snapshot(game))
+        }
+
+        /// Tiles where cargo can be deposited.
+        pub fn get_base_tiles() -> Vec<Coord> {
+            BaseTiles::<T>::iter_keys().collect()
+        }
+
+        /// First block at which the account can call `start_game`, `None` if it has no lives left this season.
+        pub fn get_respawn_block(who: T::AccountId) -> Option<BlockNumberFor<T>> {
+            if Self::lives_left(&who) == Some(0) {
+                return None;
+            }
+            let now = <frame_system::Pallet<T>>::block_number();
+            Some(RespawnAt::<T>::get(&who).map_or(now, |at| at.max(now)))
+        }
+
+        /// Ships the account can still lose this season, `None` if lives are unlimited.
+        pub fn get_lives_left(who: T::AccountId) -> Option<u32> {
+            Self::lives_left(&who)
+        }
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+            let mut weight = Self::process_map_cleanup();
+
+            // Collected up front, since the next leg of a route is inserted into `Flights` while landing
+            let landed: Vec<_> = Flights::<T>::iter()
+                .filter(|(_, _, flight)| flight.end < now)
+                .collect();
+
+            let sweep_cap = SweepCollectCap::<T>::get().unwrap_or(SWEEP_COLLECT_CAP);
+            let black_holes = BlackHoles::<T>::get();
+            weight += T::DbWeight::get().reads(2);
+
+            for (user, ship_id, flight) in landed {
+                Flights::<T>::remove(&user, ship_id);
+                weight += T::DbWeight::get().writes(1);
+                runtime_print!("[on_init] Flight removed {:?} ship {}", user, ship_id);
+
+                weight += T::DbWeight::get().reads(1);
+                let Some(mut ship) = ActiveShips::<T>::get(&user, ship_id) else {
+                    continue;
+                };
+                let game = ship.game;
+                let wrap = Self::wrap(game);
+                weight += T::DbWeight::get().reads(3);
+                let mut coord: Coord = flight.to.clone();
+
+                // A black hole close to the destination pulls the ship one tile towards itself.
+                // Black holes are part of the main map, arenas have none.
+                if let Some(hole) = black_holes.iter().find(|hole| {
+                    game == MAIN_GAME
+                        && get_distance((*hole).clone(), coord.clone(), wrap)
+                            <= BLACK_HOLE_PULL_RADIUS
+                }) {
+                    let pulled = step_towards(&coord, hole, wrap);
+                    if pulled != coord {
+                        runtime_print!("[on_init] Ship pulled by black hole to {:?}", pulled);
+                        Self::deposit_event(Event::ShipPulled {
+                            owner: user.clone(),
+                            ship_id,
+                            from: coord,
+                            to: pulled.clone(),
+                        });
+                        coord = pulled;
+                    }
+                }
+
+                // Landing only registers a claim, the asteroid is handed out in `on_finalize`
+                if Asteroids::<T>::contains_key(game, &coord) {
+                    match Self::add_claim(game, &user, ship_id, coord.clone(), flight.start) {
+                        // Claim write plus the collection done when the claim is resolved
+                        Ok(()) => {
+                            weight += T::DbWeight::get().reads_writes(1, 1)
+                                + T::WeightInfo::resolve_claim()
+                        }
+                        Err(_) => {
+                            runtime_print!(
+                                "[on_init] Too many claimants, landing claim dropped: {:?} ship {} at {:?}",
+                                user,
+                                ship_id,
+                                coord
+                            );
+                            weight += T::DbWeight::get().reads(1);
+                        }
+                    }
+                }
+                weight += T::DbWeight::get().reads(1);
+
+                if ship.collection_mode == CollectionMode::Sweep {
+                    let cap = sweep_cap.saturating_add(ship.stats.sweep_bonus);
+                    weight += Self::sweep_flight_path(game, &user, ship_id, &flight, cap);
+                }
+
+                if ship.effects.is_active(PowerUp::Magnet, now) {
+                    weight +=
+                        Self::pull_adjacent_asteroids(game, &user, ship_id, &coord, flight.start);
+                }
+
+                ship.pos = coord.clone();
+                ActiveShips::<T>::insert(&user, ship_id, ship);
+                weight += T::DbWeight::get().writes(1);
+
+                if Routes::<T>::contains_key(&user, ship_id) {
+                    weight += Self::advance_route(&user, ship_id, coord, now);
+                }
+                weight += T::DbWeight::get().reads(1);
+            }
+
+            weight += Self::process_tournaments(now);
+            weight += Self::process_match_queues();
+            weight += Self::process_duels(now);
+            weight += Self::process_stations(now);
+
+            // The main game and every open arena keep their own map filled with asteroids
+            let games: Vec<GameId> = core::iter::once(MAIN_GAME)
+                .chain(Arenas::<T>::iter_keys())
+                .collect();
+            weight += T::DbWeight::get().reads(games.len() as u64);
+            for game in games {
+                weight += Self::spawn_asteroids(game, now);
+            }
+
+            let hazard_zones = HazardZones::<T>::get();
+            let safe_zones: Vec<_> = SafeZones::<T>::iter().collect();
+            weight += T::DbWeight::get().reads(1 + safe_zones.len() as u64);
+
+            for (owner, ship_id, mut ship) in ActiveShips::<T>::iter() {
+                for power_up in ship.effects.clear_expired(now) {
+                    Self::deposit_event(Event::PowerUpExpired {
+                        owner: owner.clone(),
+                        ship_id,
+                        power_up,
+                    });
+                }
+
+                // A shielded ship does not lose energy, hazard zones drain the rest on top of their usual depletion.
+                // Hazard zones are part of the main map, arenas have none. Battle royale ships outside
+                // the safe zone are drained as well.
+                let depletion = if ship.effects.is_active(PowerUp::Shield, now) {
+                    0
+                } else {
+                    let outside_safe_zone = safe_zones
+                        .iter()
+                        .any(|(game, zone)| *game == ship.game && !zone.contains(&ship.pos));
+                    hazard_zones
+                        .iter()
+                        .filter(|zone| ship.game == MAIN_GAME && zone.contains(&ship.pos))
+                        .fold(ship.stats.energy_depletion, |depletion, zone| {
+                            depletion.saturating_add(zone.extra_depletion)
+                        })
+                        .saturating_add(if outside_safe_zone {
+                            SAFE_ZONE_ENERGY_DRAIN
+                        } else {
+                            0
+                        })
+                };
+                let new_energy = ship.energy.saturating_sub(depletion);
+
+                if new_energy == 0 {
+                    runtime_print!(
+                        "[on_init] Ship has no energy and is deactivated: {:?} ship {}",
+                        owner,
+                        ship_id
+                    );
+                    ActiveShips::<T>::remove(&owner, ship_id);
+                    Routes::<T>::remove(&owner, ship_id);
+                    weight += T::DbWeight::get().writes(2);
+
+                    if !ship.cargo.is_empty() {
+                        weight += Self::drop_wreck(
+                            ship.game,
+                            &owner,
+                            ship_id,
+                            ship.pos.clone(),
+                            ship.cargo,
+                            now,
+                        );
+                    }
+
+                    Self::deposit_event(Event::EnergyDepleted {
+                        owner: owner.clone(),
+                        ship_id,
+                    });
+                    // The account stops counting as a player of the game once its last ship there is gone.
+                    // Only then it waits to respawn and loses a life
+                    if !Self::plays_in(&owner, ship.game) {
+                        weight += Self::record_lost_ship(&owner, now);
+                        PlayersCount::<T>::mutate(ship.game, |player_count| {
+                            *player_count = player_count.saturating_sub(1);
+                        });
+                        weight += T::DbWeight::get().writes(1);
+                    }
+                    weight += T::DbWeight::get().reads(1);
+
+                    continue;
+                }
+
+                ActiveShips::<T>::insert(
+                    &owner,
+                    ship_id,
+                    Starship {
+                        energy: new_energy,
+                        ..ship
+                    },
+                );
 
-                Flights::<T>::remove(&user);
                 weight += T::DbWeight::get().writes(1);
-                runtime_print!("[on_init] Flight removed {:?}", user);
             }
 
-            for (coord, (as_id, ttl_block)) in Asteroids::<T>::iter() {
-                if ttl_block < now {
-                    weight += Self::remove_asteroid::<T>(as_id, coord.clone());
+            weight += Self::process_safe_zones(now);
+            weight += Self::process_matches(now);
+
+            weight
+        }
+
+        fn on_finalize(_now: BlockNumberFor<T>) {
+            Self::resolve_claims();
+        }
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        #[pallet::call_index(0)]
+        #[pallet::weight(T::WeightInfo::start_flight())]
+        pub fn start_flight(origin: OriginFor<T>, ship_id: ShipId, coord: Coord) -> DispatchResult {
+            // Check that the extrinsic was signed and get the signer.
+            let who = ensure_signed(origin)?;
+
+            if Flights::<T>::contains_key(&who, ship_id) {
+                return Err(Error::<T>::FlightAlreadyInProgress.into());
+            }
+
+            if !ActiveShips::<T>::contains_key(&who, ship_id) {
+                return Err(Error::<T>::NoActiveShip.into());
+            }
+
+            let ship_coord = ActiveShips::<T>::get(&who, ship_id).unwrap();
+            let from_coord = ship_coord.pos.clone();
+            let game = ship_coord.game;
+
+            if !Self::is_in_bounds(game, &coord) {
+                runtime_print!("[start_flight] Coordinates are out of bounds: {:?}", coord);
+                return Err(Error::<T>::InvalidCoordinates.into());
+            }
+
+            if Self::is_path_blocked(game, &from_coord, &coord) {
+                runtime_print!("[start_flight] Path to {:?} is blocked", coord);
+                return Err(Error::<T>::PathBlocked.into());
+            }
+
+            let block_number = <frame_system::Pallet<T>>::block_number();
+            let end_block = Self::insert_flight(
+                &who,
+                ship_id,
+                from_coord.clone(),
+                coord.clone(),
+                block_number,
+                Self::flight_duration(&ship_coord, block_number),
+            );
+            runtime_print!("[on_init] Flight added {:?} ship {}", who, ship_id);
+
+            Self::deposit_event(Event::FlightStarted {
+                owner: who.clone(),
+                ship_id,
+                from: from_coord.clone(),
+                to: coord.clone(),
+                end: end_block,
+                nft_skin: ship_coord.nft_skin,
+                wrap: Self::flight_wrap(game, &from_coord, &coord),
+            });
+
+            Ok(())
+        }
+
+        #[pallet::call_index(1)]
+        #[pallet::weight(T::WeightInfo::try_to_collect_resource())]
+        pub fn try_to_collect_resource(
+            origin: OriginFor<T>,
+            ship_id: ShipId,
+            coord: Coord,
+        ) -> DispatchResult {
+            // Ensure the call is signed and extract the caller's account
+            let who = ensure_signed(origin)?;
+
+            // The player cannot collect resources while their ship is in flight
+            if Flights::<T>::contains_key(&who, ship_id) {
+                runtime_print!("[try_to_collect_resource] Ship is still in flight");
+                return Err(Error::<T>::ShipInFlight.into());
+            }
+
+            // The player must have an active ship on the map
+            let ship_coord =
+                ActiveShips::<T>::get(&who, ship_id).ok_or(Error::<T>::NoActiveShip)?;
+
+            // Calculate the Manhattan distance between the ship and the asteroid
+            let game = ship_coord.game;
+            let distance = get_distance(ship_coord.pos.clone(), coord.clone(), Self::wrap(game));
+
+            if distance > ship_coord.stats.collection_radius {
+                runtime_print!(
+            "[try_to_collect_resource] Too far to collect resource at coord {:?}, distance: {}",
+            coord, distance
+        );
+                return Err(Error::<T>::TooFarFromResource.into());
+            }
+
+            let hold_full = u64::from(ship_coord.stats.cargo_capacity) <= ship_coord.cargo.total();
+            if hold_full
+                && Asteroids::<T>::get(game, &coord).is_some_and(|(kind, _)| kind.is_cargo())
+            {
+                runtime_print!("[try_to_collect_resource] Cargo hold is full");
+                return Err(Error::<T>::CargoFull.into());
+            }
+
+            // The asteroid is collected in `on_finalize`, together with every other claim of this block.
+            // A ship that is standing still has no flight, so the current block is used as its start.
+            let block_number = <frame_system::Pallet<T>>::block_number();
+            Self::add_claim(game, &who, ship_id, coord.clone(), block_number)?;
+            runtime_print!(
+                "[try_to_collect_resource] Claim registered for resource at coord {:?}",
+                coord
+            );
+            Ok(())
+        }
+
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::WeightInfo::start_game())]
+        pub fn start_game(
+            origin: OriginFor<T>,
+            coord: Coord,
+            nft_skin: u32,
+            class: ShipClass,
+        ) -> DispatchResult {
+            // Check that the extrinsic was signed and get the signer.
+            let who = ensure_signed(origin)?;
+
+            Self::enter_game(who, MAIN_GAME, coord, nft_skin, class)
+        }
+
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::WeightInfo::redirect_flight())]
+        pub fn redirect_flight(
+            origin: OriginFor<T>,
+            ship_id: ShipId,
+            new_coord: Coord,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let flight = Flights::<T>::get(&who, ship_id).ok_or(Error::<T>::NoFlightInProgress)?;
+            let mut ship = ActiveShips::<T>::get(&who, ship_id).ok_or(Error::<T>::NoActiveShip)?;
+
+            // Changing course costs energy, and the ship must not run dry because of it
+            if ship.energy <= REDIRECT_ENERGY_PENALTY {
+                runtime_print!(
+                    "[redirect_flight] Not enough energy to redirect: {:?}, has only {}",
+                    who,
+                    ship.energy
+                );
+                return Err(Error::<T>::NotEnoughEnergy.into());
+            }
+
+            let block_number = <frame_system::Pallet<T>>::block_number();
+            let elapsed: u32 = block_number.saturating_sub(flight.start).saturated_into();
+            let duration: u32 = flight.end.saturating_sub(flight.start).saturated_into();
+            let from_coord = get_interpolated_position(
+                &flight.from,
+                &flight.to,
+                elapsed,
+                duration,
+                Self::wrap(ship.game),
+            );
+
+            if !Self::is_in_bounds(ship.game, &new_coord) {
+                runtime_print!(
+                    "[redirect_flight] Coordinates are out of bounds: {:?}",
+                    new_coord
+                );
+                return Err(Error::<T>::InvalidCoordinates.into());
+            }
+
+            if Self::is_path_blocked(ship.game, &from_coord, &new_coord) {
+                runtime_print!("[redirect_flight] Path to {:?} is blocked", new_coord);
+                return Err(Error::<T>::PathBlocked.into());
+            }
+
+            ship.pos = from_coord.clone();
+            ship.energy = ship.energy.saturating_sub(REDIRECT_ENERGY_PENALTY);
+            let nft_skin = ship.nft_skin;
+            let game = ship.game;
+            let duration = Self::flight_duration(&ship, block_number);
+            ActiveShips::<T>::insert(&who, ship_id, ship);
+
+            // A new course replaces whatever was left of a planned route
+            Routes::<T>::remove(&who, ship_id);
+
+            let end_block = Self::insert_flight(
+                &who,
+                ship_id,
+                from_coord.clone(),
+                new_coord.clone(),
+                block_number,
+                duration,
+            );
+            runtime_print!(
+                "[redirect_flight] Flight redirected {:?} from {:?} to {:?}",
+                who,
+                from_coord,
+                new_coord
+            );
+
+            let wrap = Self::flight_wrap(game, &from_coord, &new_coord);
+            Self::deposit_event(Event::FlightRedirected {
+                owner: who,
+                ship_id,
+                from: from_coord,
+                to: new_coord,
+                end: end_block,
+                nft_skin,
+                wrap,
+            });
+
+            Ok(())
+        }
+
+        #[pallet::call_index(7)]
+        #[pallet::weight(T::WeightInfo::plan_route())]
+        pub fn plan_route(
+            origin: OriginFor<T>,
+            ship_id: ShipId,
+            waypoints: BoundedVec<Coord, ConstU32<MAX_ROUTE_WAYPOINTS>>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            if Flights::<T>::contains_key(&who, ship_id) {
+                return Err(Error::<T>::FlightAlreadyInProgress.into());
+            }
+
+            let mut ship = ActiveShips::<T>::get(&who, ship_id).ok_or(Error::<T>::NoActiveShip)?;
+
+            let mut waypoints = waypoints.into_inner();
+            if waypoints.is_empty() {
+                return Err(Error::<T>::EmptyRoute.into());
+            }
+
+            if ship.energy <= ROUTE_LEG_ENERGY_COST {
+                return Err(Error::<T>::NotEnoughEnergy.into());
+            }
+
+            // Every leg is checked up front, so the route cannot get stuck halfway
+            let mut leg_start = &ship.pos;
+            for waypoint in waypoints.iter() {
+                if !Self::is_in_bounds(ship.game, waypoint) {
+                    runtime_print!("[plan_route] Coordinates are out of bounds: {:?}", waypoint);
+                    return Err(Error::<T>::InvalidCoordinates.into());
+                }
+                if Self::is_path_blocked(ship.game, leg_start, waypoint) {
+                    runtime_print!("[plan_route] Path to {:?} is blocked", waypoint);
+                    return Err(Error::<T>::PathBlocked.into());
+                }
+                leg_start = waypoint;
+            }
+
+            // The first waypoint is flown to right away, the rest waits in `Routes`
+            let block_number = <frame_system::Pallet<T>>::block_number();
+            let first = waypoints.remove(0);
+            let from_coord = ship.pos.clone();
+            ship.energy = ship.energy.saturating_sub(ROUTE_LEG_ENERGY_COST);
+            let nft_skin = ship.nft_skin;
+            let game = ship.game;
+            let duration = Self::flight_duration(&ship, block_number);
+            ActiveShips::<T>::insert(&who, ship_id, ship);
+
+            let end_block = Self::insert_flight(
+                &who,
+                ship_id,
+                from_coord.clone(),
+                first.clone(),
+                block_number,
+                duration,
+            );
+
+            // Kept even when empty, so the landing on the last waypoint is reported too
+            Routes::<T>::insert(&who, ship_id, BoundedVec::truncate_from(waypoints));
+            runtime_print!("[plan_route] Route planned {:?} ship {}", who, ship_id);
+
+            let wrap = Self::flight_wrap(game, &from_coord, &first);
+            Self::deposit_event(Event::FlightStarted {
+                owner: who,
+                ship_id,
+                from: from_coord,
+                to: first,
+                end: end_block,
+                nft_skin,
+                wrap,
+            });
+
+            Ok(())
+        }
+
+        #[pallet::call_index(10)]
+        #[pallet::weight(T::WeightInfo::upgrade_ship())]
+        pub fn upgrade_ship(origin: OriginFor<T>, kind: UpgradeKind) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let mut upgrades = ShipUpgrades::<T>::get(&who);
+            let level = upgrades.level(kind);
+            if level >= kind.max_level() {
+                return Err(Error::<T>::UpgradeMaxed.into());
+            }
+
+            // Every level costs more than the previous one
+            let cost = UPGRADE_BASE_COST.saturating_mul(u64::from(level) + 1);
+            AccountResources::<T>::try_mutate(
+                &who,
+                AsteroidKind::Gold,
+                |gold| -> DispatchResult {
+                    if *gold < cost {
+                        runtime_print!(
+                            "[upgrade_ship] Player does not have enough Gold: {:?}, has only {}",
+                            who,
+                            *gold
+                        );
+                        return Err(Error::<T>::NotEnoughGold.into());
+                    }
+                    *gold = gold.saturating_sub(cost);
+                    Ok(())
+                },
+            )?;
+
+            *upgrades.level_mut(kind) = level + 1;
+            ShipUpgrades::<T>::insert(&who, upgrades);
+            runtime_print!(
+                "[upgrade_ship] {:?} upgraded {:?} to {}",
+                who,
+                kind,
+                level + 1
+            );
+
+            Self::deposit_event(Event::ShipUpgraded {
+                owner: who,
+                kind,
+                level: level + 1,
+                cost,
+            });
+            Ok(())
+        }
+
+        #[pallet::call_index(14)]
+        #[pallet::weight(T::WeightInfo::deposit_cargo())]
+        pub fn deposit_cargo(origin: OriginFor<T>, ship_id: ShipId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            if Flights::<T>::contains_key(&who, ship_id) {
+                return Err(Error::<T>::ShipInFlight.into());
+            }
+
+            let mut ship = ActiveShips::<T>::get(&who, ship_id).ok_or(Error::<T>::NoActiveShip)?;
+
+            if !Self::is_base(ship.game, &ship.pos) {
+                runtime_print!("[deposit_cargo] Ship is not at a base: {:?}", ship.pos);
+                return Err(Error::<T>::NotAtBase.into());
+            }
+
+            if ship.cargo.is_empty() {
+                return Err(Error::<T>::CargoEmpty.into());
+            }
+
+            let cargo = core::mem::take(&mut ship.cargo);
+            ActiveShips::<T>::insert(&who, ship_id, ship);
+
+            Self::add_resource_to_account(&who, AsteroidKind::Gold, cargo.gold);
+            Self::add_resource_to_account(&who, AsteroidKind::Dot0, cargo.dot);
+
+            Self::deposit_event(Event::CargoDeposited {
+                owner: who,
+                ship_id,
+                gold: cargo.gold,
+                dot: cargo.dot,
+            });
+            Ok(())
+        }
+
+        #[pallet::call_index(8)]
+        #[pallet::weight(T::WeightInfo::set_collection_mode())]
+        pub fn set_collection_mode(
+            origin: OriginFor<T>,
+            ship_id: ShipId,
+            mode: CollectionMode,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            ActiveShips::<T>::try_mutate(&who, ship_id, |maybe_ship| -> DispatchResult {
+                let ship = maybe_ship.as_mut().ok_or(Error::<T>::NoActiveShip)?;
+                ship.collection_mode = mode;
+                Ok(())
+            })?;
+            runtime_print!("[set_collection_mode] {:?} switched to {:?}", who, mode);
+
+            Self::deposit_event(Event::CollectionModeChanged {
+                owner: who,
+                ship_id,
+                mode,
+            });
+            Ok(())
+        }
+
+        #[pallet::call_index(26)]
+        #[pallet::weight(T::WeightInfo::create_arena())]
+        pub fn create_arena(origin: OriginFor<T>, config: ArenaConfig) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            if config.map_size == 0
+                || config.map_size > MAX_MAP_ROWS
+                || config.max_asteroids == 0
+                || config.max_asteroids > MAX_ARENA_ASTEROIDS
+                || config.max_players == 0
+            {
+                runtime_print!("[create_arena] Invalid arena config: {:?}", config);
+                return Err(Error::<T>::InvalidArenaConfig.into());
+            }
+
+            Self::ensure_arena_slot(&who, true)?;
+            Self::open_arena(who, config);
+            Ok(())
+        }
+
+        #[pallet::call_index(27)]
+        #[pallet::weight(T::WeightInfo::join_arena())]
+        pub fn join_arena(
+            origin: OriginFor<T>,
+            arena_id: GameId,
+            coord: Coord,
+            nft_skin: u32,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let arena = Arenas::<T>::get(arena_id).ok_or(Error::<T>::ArenaNotFound)?;
+
+            // The arena of a tournament is open to its registered accounts once the match started
+            if let Some(tournament) = Tournaments::<T>::get(arena_id) {
+                if !tournament.started {
+                    return Err(Error::<T>::TournamentNotStarted.into());
+                }
+                if !tournament.players.contains(&who) {
+                    return Err(Error::<T>::NotRegistered.into());
+                }
+            }
+
+            // Only the two duelists play in the arena of a duel
+            if let Some(duel) = Duels::<T>::get(arena_id) {
+                if who != duel.challenger && who != duel.opponent {
+                    return Err(Error::<T>::NotRegistered.into());
+                }
+            }
+
+            // and only the players of a match started from the queue in its arena
+            if let Some(running) = Matches::<T>::get(arena_id) {
+                if !running.players.contains(&who) {
+                    return Err(Error::<T>::NotRegistered.into());
+                }
+                // The ships of a battle royale are placed when it starts, a player knocked out stays out
+                if running.mode == MatchMode::BattleRoyale {
+                    return Err(Error::<T>::MatchAlreadyStarted.into());
+                }
+            }
+
+            // More ships of an account already in the arena do not take another seat
+            if !Self::plays_in(&who, arena_id)
+                && PlayersCount::<T>::get(arena_id) >= arena.config.max_players
+            {
+                runtime_print!("[join_arena] Arena {} is full", arena_id);
+                return Err(Error::<T>::ArenaFull.into());
+            }
+
+            Self::enter_game(who, arena_id, coord, nft_skin, ShipClass::default())
+        }
+
+        #[pallet::call_index(28)]
+        #[pallet::weight(T::WeightInfo::close_arena())]
+        pub fn close_arena(origin: OriginFor<T>, arena_id: GameId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let arena = Arenas::<T>::get(arena_id).ok_or(Error::<T>::ArenaNotFound)?;
+            if arena.creator != who {
+                return Err(Error::<T>::NotArenaCreator.into());
+            }
+
+            // A tournament closes its arena itself once it is over
+            if Tournaments::<T>::contains_key(arena_id) {
+                return Err(Error::<T>::ArenaInTournament.into());
+            }
+            if Duels::<T>::contains_key(arena_id) {
+                return Err(Error::<T>::ArenaInDuel.into());
+            }
+            if Matches::<T>::contains_key(arena_id) {
+                return Err(Error::<T>::ArenaInMatch.into());
+            }
+
+            if PlayersCount::<T>::get(arena_id) > 0 {
+                runtime_print!("[close_arena] Arena {} still has players", arena_id);
+                return Err(Error::<T>::ArenaNotEmpty.into());
+            }
+
+            Self::remove_arena(arena_id);
+            Ok(())
+        }
+
+        #[pallet::call_index(29)]
+        #[pallet::weight(T::WeightInfo::create_tournament())]
+        pub fn create_tournament(
+            origin: OriginFor<T>,
+            config: ArenaConfig,
+            entry_fee: u64,
+            payout: BoundedVec<Percent, ConstU32<MAX_PAYOUT_PLACES>>,
+            start: BlockNumberFor<T>,
+            duration: u32,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin.clone())?;
+
+            let now = <frame_system::Pallet<T>>::block_number();
+            let payout_total: u32 = payout.iter().map(|share| share.deconstruct() as u32).sum();
+            if start <= now
+                || duration == 0
+                || duration > MAX_MATCH_DURATION
+                || payout_total != 100
+                || config.max_players > MAX_TOURNAMENT_PLAYERS
+            {
+                runtime_print!("[create_tournament] Invalid tournament settings");
+                return Err(Error::<T>::InvalidTournament.into());
+            }
+
+            // The match is played in an arena of its own
+            Self::create_arena(origin, config)?;
+            let game = NextArenaId::<T>::get() - 1;
+
+            let end = start.saturating_add(duration.into());
+            Tournaments::<T>::insert(
+                game,
+                Tournament {
+                    organizer: who.clone(),
+                    entry_fee,
+                    payout,
+                    start,
+                    end,
+                    pot: 0,
+                    players: BoundedVec::new(),
+                    started: false,
+                },
+            );
+
+            runtime_print!("[create_tournament] Tournament created in arena {}", game);
+            Self::deposit_event(Event::TournamentCreated {
+                game,
+                organizer: who,
+                entry_fee,
+                start,
+                end,
+            });
+            Ok(())
+        }
+
+        #[pallet::call_index(30)]
+        #[pallet::weight(T::WeightInfo::register_for_tournament())]
+        pub fn register_for_tournament(origin: OriginFor<T>, game: GameId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let mut tournament =
+                Tournaments::<T>::get(game).ok_or(Error::<T>::TournamentNotFound)?;
+            if tournament.started {
+                return Err(Error::<T>::RegistrationClosed.into());
+            }
+            if tournament.players.contains(&who) {
+                return Err(Error::<T>::AlreadyRegistered.into());
+            }
+
+            let max_players = Arenas::<T>::get(game).map_or(0, |arena| arena.config.max_players);
+            if tournament.players.len() as u32 >= max_players {
+                return Err(Error::<T>::TournamentFull.into());
+            }
+
+            // The entry fee is paid with the DOT banked by the account
+            Self::escrow_dot(&who, tournament.entry_fee)?;
+
+            tournament.pot = tournament.pot.saturating_add(tournament.entry_fee);
+            tournament
+                .players
+                .try_push(who.clone())
+                .map_err(|_| Error::<T>::TournamentFull)?;
+            Tournaments::<T>::insert(game, tournament);
+
+            Self::deposit_event(Event::TournamentRegistered { game, who });
+            Ok(())
+        }
+
+        #[pallet::call_index(31)]
+        #[pallet::weight(T::WeightInfo::join_queue())]
+        pub fn join_queue(origin: OriginFor<T>, mode: MatchMode) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            // An account waits for one match at a time
+            if MatchMode::ALL
+                .iter()
+                .any(|mode| MatchQueue::<T>::get(mode).contains(&who))
+            {
+                return Err(Error::<T>::AlreadyQueued.into());
+            }
+
+            // Only accounts that could launch a ship right now are queued
+            Self::free_ship_slot(&who)?;
+
+            MatchQueue::<T>::try_mutate(mode, |queue| queue.try_push(who.clone()))
+                .map_err(|_| Error::<T>::QueueFull)?;
+
+            runtime_print!("[join_queue] {:?} waits for a {:?} match", who, mode);
+            Self::deposit_event(Event::QueueJoined { mode, who });
+            Ok(())
+        }
+
+        #[pallet::call_index(32)]
+        #[pallet::weight(T::WeightInfo::leave_queue())]
+        pub fn leave_queue(origin: OriginFor<T>, mode: MatchMode) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            MatchQueue::<T>::try_mutate(mode, |queue| {
+                let index = queue
+                    .iter()
+                    .position(|queued| *queued == who)
+                    .ok_or(Error::<T>::NotQueued)?;
+                queue.remove(index);
+                Ok::<_, Error<T>>(())
+            })?;
+
+            Self::deposit_event(Event::QueueLeft { mode, who });
+            Ok(())
+        }
+
+        #[pallet::call_index(33)]
+        #[pallet::weight(T::WeightInfo::challenge())]
+        pub fn challenge(
+            origin: OriginFor<T>,
+            opponent: T::AccountId,
+            wager: u64,
+            duration: u32,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            if opponent == who || wager == 0 || duration == 0 || duration > MAX_MATCH_DURATION {
+                return Err(Error::<T>::InvalidChallenge.into());
+            }
+
+            let challenge_id = NextChallengeId::<T>::get();
+            let expires =
+                <frame_system::Pallet<T>>::block_number().saturating_add(CHALLENGE_EXPIRY.into());
+            ChallengeExpiries::<T>::try_mutate(expires, |due| due.try_push(challenge_id))
+                .map_err(|_| Error::<T>::TooManyChallenges)?;
+
+            Self::escrow_dot(&who, wager)?;
+
+            NextChallengeId::<T>::put(challenge_id.wrapping_add(1));
+            Challenges::<T>::insert(
+                challenge_id,
+                Challenge {
+                    challenger: who.clone(),
+                    opponent: opponent.clone(),
+                    wager,
+                    duration,
+                    expires,
+                },
+            );
+
+            runtime_print!("[challenge] {:?} challenged {:?}", who, opponent);
+            Self::deposit_event(Event::ChallengeIssued {
+                challenge_id,
+                challenger: who,
+                opponent,
+                wager,
+                expires,
+            });
+            Ok(())
+        }
+
+        #[pallet::call_index(34)]
+        #[pallet::weight(T::WeightInfo::accept_challenge())]
+        pub fn accept_challenge(origin: OriginFor<T>, challenge_id: u32) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let challenge =
+                Challenges::<T>::get(challenge_id).ok_or(Error::<T>::ChallengeNotFound)?;
+            if challenge.opponent != who {
+                return Err(Error::<T>::NotChallengeOpponent.into());
+            }
+
+            // The duel arena counts against the challenger like any arena they opened
+            Self::ensure_arena_slot(&challenge.challenger, false)?;
+
+            Self::escrow_dot(&who, challenge.wager)?;
+            Challenges::<T>::remove(challenge_id);
+
+            // The duel is played in a private arena the size of a quick match
+            let config = MatchMode::Quick.arena_config();
+            let positions = Self::start_positions(config.map_size, 2);
+            let game = Self::open_arena(challenge.challenger.clone(), config);
+            for (duelist, coord) in [&challenge.challenger, &who].into_iter().zip(positions) {
+                Self::enter_game(duelist.clone(), game, coord, 0, ShipClass::default())?;
+            }
+
+            let end =
+                <frame_system::Pallet<T>>::block_number().saturating_add(challenge.duration.into());
+            Duels::<T>::insert(
+                game,
+                Duel {
+                    challenger: challenge.challenger.clone(),
+                    opponent: who.clone(),
+                    wager: challenge.wager,
+                    end,
+                },
+            );
+
+            runtime_print!("[accept_challenge] Duel {} started", game);
+            Self::deposit_event(Event::DuelStarted {
+                game,
+                challenge_id,
+                challenger: challenge.challenger,
+                opponent: who,
+                end,
+            });
+            Ok(())
+        }
+
+        #[pallet::call_index(35)]
+        #[pallet::weight(T::WeightInfo::claim_tile())]
+        pub fn claim_tile(origin: OriginFor<T>, coord: Coord, stake: u64) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            if !Self::is_in_bounds(MAIN_GAME, &coord) {
+                runtime_print!("[claim_tile] Coordinates are out of bounds: {:?}", coord);
+                return Err(Error::<T>::InvalidCoordinates.into());
+            }
+
+            // A free region needs the minimum stake, an owned one is taken over by staking more than its owner
+            let region = get_region(&coord, TERRITORY_SIZE);
+            let previous = Territories::<T>::get(&region);
+            if let Some(territory) = &previous {
+                if territory.owner == who {
+                    return Err(Error::<T>::AlreadyTerritoryOwner.into());
+                }
+                if stake <= territory.stake {
+                    return Err(Error::<T>::TerritoryStakeTooLow.into());
+                }
+            } else if stake < MIN_TERRITORY_STAKE {
+                return Err(Error::<T>::TerritoryStakeTooLow.into());
+            }
+
+            Self::pay_gold(&who, stake)?;
+
+            // Only a region taken over refunds the stake of its previous owner
+            if let Some(territory) = &previous {
+                Self::add_resource_to_account(
+                    &territory.owner,
+                    AsteroidKind::Gold,
+                    territory.stake,
+                );
+            }
+            Territories::<T>::insert(
+                &region,
+                Territory {
+                    owner: who.clone(),
+                    stake,
+                },
+            );
+
+            runtime_print!("[claim_tile] {:?} owns region {:?}", who, region);
+            Self::deposit_event(Event::TerritoryClaimed {
+                region,
+                owner: who,
+                stake,
+                previous_owner: previous.map(|territory| territory.owner),
+            });
+            Ok(())
+        }
+
+        #[pallet::call_index(37)]
+        #[pallet::weight(T::WeightInfo::build_station())]
+        pub fn build_station(
+            origin: OriginFor<T>,
+            ship_id: ShipId,
+            output: StationOutput,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            if Flights::<T>::contains_key(&who, ship_id) {
+                return Err(Error::<T>::ShipInFlight.into());
+            }
+
+            let ship = ActiveShips::<T>::get(&who, ship_id).ok_or(Error::<T>::NoActiveShip)?;
+
+            // Stations are part of the main map, arenas are too short-lived for them
+            if ship.game != MAIN_GAME {
+                return Err(Error::<T>::InvalidCoordinates.into());
+            }
+
+            if Stations::<T>::contains_key(&ship.pos) {
+                return Err(Error::<T>::StationExists.into());
+            }
+
+            if StationsCount::<T>::get(&who) >= MAX_STATIONS_PER_ACCOUNT {
+                return Err(Error::<T>::TooManyStations.into());
+            }
+
+            Self::pay_gold(&who, STATION_BUILD_COST)?;
+
+            let next_yield =
+                <frame_system::Pallet<T>>::block_number() + STATION_YIELD_INTERVAL.into();
+            Stations::<T>::insert(
+                &ship.pos,
+                Station {
+                    owner: who.clone(),
+                    output,
+                    level: 1,
+                    stored_energy: 0,
+                    next_yield,
+                },
+            );
+            StationsCount::<T>::mutate(&who, |count| *count = count.saturating_add(1));
+
+            runtime_print!(
+                "[build_station] {:?} built a station at {:?}",
+                who,
+                ship.pos
+            );
+            Self::deposit_event(Event::StationBuilt {
+                owner: who,
+                coord: ship.pos,
+                output,
+            });
+            Ok(())
+        }
+
+        #[pallet::call_index(38)]
+        #[pallet::weight(T::WeightInfo::upgrade_station())]
+        pub fn upgrade_station(origin: OriginFor<T>, coord: Coord) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let mut station = Stations::<T>::get(&coord).ok_or(Error::<T>::StationNotFound)?;
+            if station.owner != who {
+                return Err(Error::<T>::NotStationOwner.into());
+            }
+            if station.level >= MAX_STATION_LEVEL {
+                return Err(Error::<T>::UpgradeMaxed.into());
+            }
+
+            // Every level costs more than the previous one
+            Self::pay_gold(
+                &who,
+                STATION_BUILD_COST.saturating_mul(u64::from(station.level) + 1),
+            )?;
+
+            station.level += 1;
+            let level = station.level;
+            Stations::<T>::insert(&coord, station);
+
+            Self::deposit_event(Event::StationUpgraded { coord, level });
+            Ok(())
+        }
+
+        // ! -------------------------------------------
+        // ! Admin calls are implemented to allow faster testing of the game with different parameters.
+        #[pallet::call_index(3)]
+        #[pallet::weight(T::WeightInfo::admin_set_map_size())]
+        pub fn admin_set_map_size(origin: OriginFor<T>, size: u32) -> DispatchResult {
+            ensure_root(origin)?;
+
+            if size == 0 || size > MAX_MAP_ROWS {
+                runtime_print!("[set_map_size] Invalid map size: {}", size);
+                return Err(Error::<T>::InvalidMapSize.into());
+            }
+
+            Self::resize_map(size);
+            runtime_print!("[set_map_size] Map size set to: {}", size);
+            Self::deposit_event(Event::MapSizeChanged { size });
+            Ok(())
+        }
+
+        #[pallet::call_index(4)]
+        #[pallet::weight(T::WeightInfo::admin_set_max_asteroids_count())]
+        pub fn admin_set_max_asteroids_count(origin: OriginFor<T>, count: u32) -> DispatchResult {
+            ensure_root(origin)?;
+
+            MaxAsteroidsCount::<T>::put(count);
+            runtime_print!(
+                "[set_max_asteroids_count] Max asteroids count set to: {}",
+                count
+            );
+            Ok(())
+        }
+
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::WeightInfo::admin_reset_game())]
+        pub fn admin_reset_game(origin: OriginFor<T>) -> DispatchResult {
+            ensure_root(origin)?;
+
+            for (owner, ship_id, mut ship) in ActiveShips::<T>::iter() {
+                ship.energy = DEFAULT_ENERGY;
+                ship.pos = Coord { x: 0, y: 0 };
+
+                ActiveShips::<T>::insert(&owner, ship_id, ship);
+                runtime_print!(
+                    "[admin_reset_game] Reset ship for {:?} to energy={} pos=(0,0)",
+                    owner,
+                    DEFAULT_ENERGY
+                );
+            }
+
+            for (user, ship_id, _) in Flights::<T>::iter() {
+                Flights::<T>::remove(&user, ship_id);
+                runtime_print!("[admin_reset_game] Cleared flight for {:?}", user);
+            }
+
+            let _ = Routes::<T>::clear(u32::MAX, None);
+            Ok(())
+        }
+
+        #[pallet::call_index(11)]
+        #[pallet::weight(T::WeightInfo::admin_set_skin())]
+        pub fn admin_set_skin(
+            origin: OriginFor<T>,
+            skin_id: u32,
+            skin: SkinInfo,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            Skins::<T>::insert(skin_id, skin);
+            runtime_print!("[set_skin] Skin registered: {}", skin_id);
+
+            Self::deposit_event(Event::SkinRegistered { skin_id });
+            Ok(())
+        }
+
+        #[pallet::call_index(12)]
+        #[pallet::weight(T::WeightInfo::admin_remove_skin())]
+        pub fn admin_remove_skin(origin: OriginFor<T>, skin_id: u32) -> DispatchResult {
+            ensure_root(origin)?;
+
+            if Skins::<T>::take(skin_id).is_none() {
+                return Err(Error::<T>::InvalidNftSkin.into());
+            }
+            runtime_print!("[remove_skin] Skin removed: {}", skin_id);
+
+            Self::deposit_event(Event::SkinRemoved { skin_id });
+            Ok(())
+        }
+
+        #[pallet::call_index(13)]
+        #[pallet::weight(T::WeightInfo::admin_set_class_stats())]
+        pub fn admin_set_class_stats(
+            origin: OriginFor<T>,
+            class: ShipClass,
+            stats: ShipClassStats,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            // A ship needs energy to exist, and flights cannot land in the block they start
+            if stats.energy == 0 || stats.stats.flight_duration == 0 {
+                return Err(Error::<T>::InvalidClassStats.into());
+            }
+
+            ClassStats::<T>::insert(class, stats.clone());
+            runtime_print!("[set_class_stats] Stats of {:?} set to {:?}", class, stats);
+
+            Self::deposit_event(Event::ClassStatsUpdated { class, stats });
+            Ok(())
+        }
+
+        #[pallet::call_index(15)]
+        #[pallet::weight(T::WeightInfo::admin_set_base_tile())]
+        pub fn admin_set_base_tile(
+            origin: OriginFor<T>,
+            coord: Coord,
+            enabled: bool,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            if enabled {
+                BaseTiles::<T>::insert(&coord, ());
+            } else {
+                BaseTiles::<T>::remove(&coord);
+            }
+            runtime_print!(
+                "[admin_set_base_tile] Base at {:?} enabled: {}",
+                coord,
+                enabled
+            );
+
+            Self::deposit_event(Event::BaseTileSet { coord, enabled });
+            Ok(())
+        }
+
+        #[pallet::call_index(9)]
+        #[pallet::weight(T::WeightInfo::admin_set_sweep_collect_cap())]
+        pub fn admin_set_sweep_collect_cap(origin: OriginFor<T>, cap: u32) -> DispatchResult {
+            ensure_root(origin)?;
+
+            SweepCollectCap::<T>::put(cap);
+            runtime_print!("[set_sweep_collect_cap] Sweep collect cap set to: {}", cap);
+            Ok(())
+        }
+
+        #[pallet::call_index(16)]
+        #[pallet::weight(T::WeightInfo::admin_set_wreck_share())]
+        pub fn admin_set_wreck_share(origin: OriginFor<T>, share: Percent) -> DispatchResult {
+            ensure_root(origin)?;
+
+            WreckShare::<T>::put(share);
+            runtime_print!("[set_wreck_share] Wreck share set to: {:?}", share);
+            Ok(())
+        }
+
+        #[pallet::call_index(18)]
+        #[pallet::weight(T::WeightInfo::admin_set_respawn_cooldown())]
+        pub fn admin_set_respawn_cooldown(origin: OriginFor<T>, blocks: u32) -> DispatchResult {
+            ensure_root(origin)?;
+
+            RespawnCooldownBlocks::<T>::put(blocks);
+            runtime_print!("[set_respawn_cooldown] Respawn cooldown set to: {}", blocks);
+            Ok(())
+        }
+
+        #[pallet::call_index(19)]
+        #[pallet::weight(T::WeightInfo::admin_set_lives_per_season())]
+        pub fn admin_set_lives_per_season(
+            origin: OriginFor<T>,
+            lives: Option<u32>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            LivesPerSeason::<T>::set(lives);
+            runtime_print!(
+                "[set_lives_per_season] Lives per season set to: {:?}",
+                lives
+            );
+            Ok(())
+        }
+
+        #[pallet::call_index(20)]
+        #[pallet::weight(T::WeightInfo::admin_start_season())]
+        pub fn admin_start_season(origin: OriginFor<T>) -> DispatchResult {
+            ensure_root(origin)?;
+
+            let season = Season::<T>::mutate(|season| {
+                *season = season.saturating_add(1);
+                *season
+            });
+            runtime_print!("[start_season] Season {} started", season);
+
+            Self::deposit_event(Event::SeasonStarted { season });
+            Ok(())
+        }
+
+        #[pallet::call_index(21)]
+        #[pallet::weight(T::WeightInfo::admin_set_black_holes())]
+        pub fn admin_set_black_holes(
+            origin: OriginFor<T>,
+            black_holes: BoundedVec<Coord, ConstU32<MAX_BLACK_HOLES>>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            runtime_print!("[set_black_holes] Black holes set to: {:?}", black_holes);
+            BlackHoles::<T>::put(black_holes);
+            Ok(())
+        }
+
+        #[pallet::call_index(22)]
+        #[pallet::weight(T::WeightInfo::admin_set_hazard_zones())]
+        pub fn admin_set_hazard_zones(
+            origin: OriginFor<T>,
+            zones: BoundedVec<HazardZone, ConstU32<MAX_HAZARD_ZONES>>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            if zones
+                .iter()
+                .any(|zone| zone.min.x > zone.max.x || zone.min.y > zone.max.y)
+            {
+                return Err(Error::<T>::InvalidHazardZone.into());
+            }
+
+            runtime_print!("[set_hazard_zones] Hazard zones set to: {:?}", zones);
+            HazardZones::<T>::put(zones);
+            Ok(())
+        }
+
+        #[pallet::call_index(23)]
+        #[pallet::weight(T::WeightInfo::admin_set_blocked_tiles())]
+        pub fn admin_set_blocked_tiles(
+            origin: OriginFor<T>,
+            tiles: BoundedVec<Coord, ConstU32<MAX_BLOCKED_TILES_PER_CALL>>,
+            blocked: bool,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            let map_size = MapSize::<T>::get().unwrap_or(MAP_SIZE);
+            if tiles.iter().any(|tile| {
+                tile.x >= map_size || tile.y >= map_size || tile.x >= MAX_MAP_ROW_BYTES * 8
+            }) {
+                return Err(Error::<T>::InvalidCoordinates.into());
+            }
+
+            for tile in tiles.iter() {
+                Self::set_blocked(tile, blocked);
+            }
+            runtime_print!(
+                "[set_blocked_tiles] {} tiles set to blocked: {}",
+                tiles.len(),
+                blocked
+            );
+            Ok(())
+        }
+
+        #[pallet::call_index(24)]
+        #[pallet::weight(T::WeightInfo::admin_upload_map())]
+        pub fn admin_upload_map(origin: OriginFor<T>, layout: MapLayout) -> DispatchResult {
+            ensure_root(origin)?;
+
+            if !Self::is_valid_map_layout(&layout) {
+                runtime_print!("[upload_map] Invalid map layout");
+                return Err(Error::<T>::InvalidMapLayout.into());
+            }
+
+            let MapLayout {
+                size,
+                blocked_rows,
+                spawn_zones,
+                base_tiles,
+            } = layout;
+
+            Self::resize_map(size);
+
+            let _ = BlockedRows::<T>::clear(u32::MAX, None);
+            for (y, row) in blocked_rows {
+                if row.iter().any(|bits| *bits != 0) {
+                    BlockedRows::<T>::insert(y, row);
+                }
+            }
+
+            SpawnZones::<T>::put(spawn_zones);
+
+            let _ = BaseTiles::<T>::clear(u32::MAX, None);
+            for base in base_tiles {
+                BaseTiles::<T>::insert(base, ());
+            }
+
+            runtime_print!("[upload_map] Map layout uploaded, size: {}", size);
+            Self::deposit_event(Event::MapLayoutUploaded { size });
+            Ok(())
+        }
+
+        #[pallet::call_index(25)]
+        #[pallet::weight(T::WeightInfo::admin_set_map_topology())]
+        pub fn admin_set_map_topology(
+            origin: OriginFor<T>,
+            topology: MapTopology,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            Topology::<T>::put(topology);
+            runtime_print!("[set_map_topology] Map topology set to: {:?}", topology);
+            Ok(())
+        }
+
+        #[pallet::call_index(36)]
+        #[pallet::weight(T::WeightInfo::admin_set_territory_tax())]
+        pub fn admin_set_territory_tax(origin: OriginFor<T>, tax: Percent) -> DispatchResult {
+            ensure_root(origin)?;
+
+            TerritoryTax::<T>::put(tax);
+            runtime_print!("[set_territory_tax] Territory tax set to: {:?}", tax);
+            Ok(())
+        }
+
+        #[pallet::call_index(17)]
+        #[pallet::weight(T::WeightInfo::admin_set_wreck_ttl())]
+        pub fn admin_set_wreck_ttl(origin: OriginFor<T>, ttl: u32) -> DispatchResult {
+            ensure_root(origin)?;
+
+            if ttl == 0 {
+                return Err(Error::<T>::InvalidWreckTtl.into());
+            }
+
+            WreckTtl::<T>::put(ttl);
+            runtime_print!("[set_wreck_ttl] Wreck TTL set to: {}", ttl);
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Fleet slot the next ship of the account takes, if the account can launch a ship right now.
+        fn free_ship_slot(who: &UserAccount<T>) -> Result<ShipId, DispatchError> {
+            let block_number = <frame_system::Pallet<T>>::block_number();
+            if RespawnAt::<T>::get(who).is_some_and(|at| block_number < at) {
+                runtime_print!("[start_game] Player is waiting to respawn: {:?}", who);
+                return Err(Error::<T>::RespawnCooldown.into());
+            }
+
+            if Self::lives_left(who) == Some(0) {
+                runtime_print!("[start_game] Player has no lives left: {:?}", who);
+                return Err(Error::<T>::NoLivesLeft.into());
+            }
+
+            // The new ship takes the lowest free slot of the fleet
+            let Some(ship_id) =
+                (0..MAX_FLEET_SIZE).find(|ship_id| !ActiveShips::<T>::contains_key(who, ship_id))
+            else {
+                runtime_print!("[start_game] Player fleet is full: {:?}", who);
+                return Err(Error::<T>::FleetFull.into());
+            };
+            Ok(ship_id)
+        }
+
+        /// Places a new ship of `who` into a game. Shared by `start_game` and `join_arena`.
+        fn enter_game(
+            who: UserAccount<T>,
+            game: GameId,
+            coord: Coord,
+            nft_skin: u32,
+            class: ShipClass,
+        ) -> DispatchResult {
+            let ship_id = Self::free_ship_slot(&who)?;
+            let first_ship = !Self::plays_in(&who, game);
+
+            if !Self::is_in_bounds(game, &coord) {
+                runtime_print!("[start_game] Coordinates are out of bounds: {:?}", coord);
+                return Err(Error::<T>::InvalidCoordinates.into());
+            }
+
+            if Self::is_tile_blocked(game, &coord) {
+                runtime_print!("[start_game] Tile is blocked: {:?}", coord);
+                return Err(Error::<T>::TileBlocked.into());
+            }
+
+            // ! We decided not to require players to pay with Gold for participation,
+            // ! since Gold can serve better as a leaderboard score.
+            // ! Instead, we chose to use DOT for participation and created a dedicated DOT prize pool for this purpose.
+            // ! So while the logic of paying to participate remains, the project now uses a shared
+            // ! DOT pool to spawn DOT asteroids — which we believe is a better approach than requiring
+            // ! Gold for entry.
+            // let user_gold = AccountResources::<T>::get(&who, AsteroidKind::Dot0);
+            // let user_gold = AccountResources::<T>::get(&who, AsteroidKind::Gold);
+            // if user_gold < 20 {
+            //     runtime_print!(
+            //         "[start_game] Player does not have enough Gold: {:?}, has only {}",
+            //         who,
+            //         user_gold
+            //     );
+            //     return Err(Error::<T>::NoneValue.into());
+            // }
+
+            let Some(perks) = Self::skin_perks(nft_skin) else {
+                runtime_print!("[start_game] Invalid nft_skin: {}", nft_skin);
+                return Err(Error::<T>::InvalidNftSkin.into());
+            };
+
+            if let Some(required) = Skins::<T>::get(nft_skin).and_then(|skin| skin.required) {
+                let has_nft = AccountResources::<T>::get(&who, required) > 0;
+                if !has_nft {
+                    runtime_print!(
+                        "[start_game] Player does not have the required NFT: {:?}",
+                        required
+                    );
+                    return Err(Error::<T>::MissingNftForSkin.into());
+                }
+            }
+
+            // The class gives the base stats. Upgrades bought with Gold are applied to every
+            // new ship of the account, together with the perks of the chosen skin
+            let class_stats = Self::class_stats(class);
+            let upgrades = ShipUpgrades::<T>::get(&who);
+            let energy = class_stats
+                .energy
+                .saturating_add(UPGRADE_ENERGY_BONUS.saturating_mul(upgrades.max_energy.into()))
+                .saturating_add(perks.bonus_energy);
+
+            ActiveShips::<T>::insert(
+                &who,
+                ship_id,
+                Starship {
+                    game,
+                    pos: coord.clone(),
+                    energy,
+                    nft_skin,
+                    collection_mode: CollectionMode::default(),
+                    class,
+                    stats: Self::new_ship_stats(&class_stats.stats, &upgrades, &perks),
+                    cargo: Cargo::default(),
+                    effects: Effects::default(),
+                },
+            );
+
+            Self::deposit_event(Event::GameStarted {
+                game,
+                owner: who.clone(),
+                ship_id,
+                coord: coord.clone(),
+                nft_skin,
+                class,
+            });
+
+            // Add the value to the DOT prize pool of the game
+            DotPrizePool::<T>::mutate(game, |pool| {
+                *pool = pool.saturating_add(DEFAULT_DOT_STAKE);
+            });
+            runtime_print!(
+                "[on_init] Active ship added {:?} ship {} coord: {:?}",
+                who,
+                ship_id,
+                coord
+            );
+
+            if first_ship {
+                PlayersCount::<T>::mutate(game, |player_count| {
+                    *player_count = player_count.saturating_add(1);
+                });
+            }
+            Ok(())
+        }
+
+        /// Stores a flight of `owner` from `from` to `to` starting at `start` and lasting `duration` blocks.
+        /// Returns the block at which the flight ends.
+        fn insert_flight(
+            owner: &UserAccount<T>,
+            ship_id: ShipId,
+            from: Coord,
+            to: Coord,
+            start: BlockNumberFor<T>,
+            duration: u32,
+        ) -> BlockNumberFor<T> {
+            let end = start + duration.into();
+            Flights::<T>::insert(
+                owner,
+                ship_id,
+                Flight {
+                    from,
+                    to,
+                    start,
+                    end,
+                },
+            );
+            end
+        }
+
+        /// Called when a ship following a planned route lands on `coord`.
+        /// Starts the next leg if the ship can pay for it, otherwise the route is dropped.
+        fn advance_route(
+            owner: &UserAccount<T>,
+            ship_id: ShipId,
+            coord: Coord,
+            now: BlockNumberFor<T>,
+        ) -> Weight {
+            let mut weight = T::DbWeight::get().reads(2);
+
+            let mut waypoints = Routes::<T>::take(owner, ship_id)
+                .unwrap_or_default()
+                .into_inner();
+            weight += T::DbWeight::get().writes(1);
+
+            Self::deposit_event(Event::WaypointReached {
+                owner: owner.clone(),
+                ship_id,
+                coord: coord.clone(),
+                remaining: waypoints.len() as u32,
+            });
+
+            if waypoints.is_empty() {
+                runtime_print!("[advance_route] Route finished {:?}", owner);
+                return weight;
+            }
+
+            let Some(mut ship) = ActiveShips::<T>::get(owner, ship_id) else {
+                return weight;
+            };
+
+            if ship.energy <= ROUTE_LEG_ENERGY_COST {
+                runtime_print!("[advance_route] Not enough energy for next leg {:?}", owner);
+                Self::deposit_event(Event::RouteAborted {
+                    owner: owner.clone(),
+                    ship_id,
+                    coord,
+                });
+                return weight;
+            }
+
+            let next = waypoints.remove(0);
+            ship.energy = ship.energy.saturating_sub(ROUTE_LEG_ENERGY_COST);
+            let nft_skin = ship.nft_skin;
+            let game = ship.game;
+            let duration = Self::flight_duration(&ship, now);
+            ActiveShips::<T>::insert(owner, ship_id, ship);
+
+            let end =
+                Self::insert_flight(owner, ship_id, coord.clone(), next.clone(), now, duration);
+            Routes::<T>::insert(owner, ship_id, BoundedVec::truncate_from(waypoints));
+            weight += T::DbWeight::get().writes(3);
+
+            let wrap = Self::flight_wrap(game, &coord, &next);
+            Self::deposit_event(Event::FlightStarted {
+                owner: owner.clone(),
+                ship_id,
+                from: coord,
+                to: next,
+                end,
+                nft_skin,
+                wrap,
+            });
+
+            weight
+        }
+
+        /// Whether a tile of the main map is blocked.
+        pub fn is_blocked(coord: &Coord) -> bool {
+            BlockedRows::<T>::get(coord.y).is_some_and(|row| is_bit_set(&row, coord.x))
+        }
+
+        /// Whether a tile of a game is blocked. Arenas have no blocked tiles.
+        fn is_tile_blocked(game: GameId, coord: &Coord) -> bool {
+            game == MAIN_GAME && Self::is_blocked(coord)
+        }
+
+        /// Whether `who` has a ship in the game.
+        fn plays_in(who: &UserAccount<T>, game: GameId) -> bool {
+            ActiveShips::<T>::iter_prefix(who).any(|(_, ship)| ship.game == game)
+        }
+
+        fn map_size(game: GameId) -> u32 {
+            if game == MAIN_GAME {
+                return MapSize::<T>::get().unwrap_or(MAP_SIZE);
+            }
+            Arenas::<T>::get(game).map_or(0, |arena| arena.config.map_size)
+        }
+
+        fn max_asteroids(game: GameId) -> u32 {
+            if game == MAIN_GAME {
+                return MaxAsteroidsCount::<T>::get().unwrap_or(MAX_ASTEROIDS_COUNT);
+            }
+            Arenas::<T>::get(game).map_or(0, |arena| arena.config.max_asteroids)
+        }
+
+        fn topology(game: GameId) -> MapTopology {
+            if game == MAIN_GAME {
+                return Topology::<T>::get();
+            }
+            Arenas::<T>::get(game).map_or(MapTopology::Bounded, |arena| arena.config.topology)
+        }
+
+        /// Size of the map when it is toroidal, `None` when it is bounded. Passed to the `utils` geometry.
+        fn wrap(game: GameId) -> Option<u32> {
+            match Self::topology(game) {
+                MapTopology::Bounded => None,
+                MapTopology::Toroidal => Some(Self::map_size(game)),
+            }
+        }
+
+        fn flight_wrap(game: GameId, from: &Coord, to: &Coord) -> MapWrap {
+            get_wrap(from, to, Self::wrap(game))
+        }
+
+        pub fn is_in_bounds(game: GameId, coord: &Coord) -> bool {
+            let map_size = Self::map_size(game);
+            coord.x < map_size && coord.y < map_size
+        }
+
+        /// Whether cargo can be deposited on a tile. The only base of an arena is the middle of its map.
+        fn is_base(game: GameId, coord: &Coord) -> bool {
+            if game == MAIN_GAME {
+                return BaseTiles::<T>::contains_key(coord);
+            }
+            let middle = Self::map_size(game) / 2;
+            coord.x == middle && coord.y == middle
+        }
+
+        fn world_snapshot(game: GameId) -> WorldSnapshot<BlockNumberFor<T>> {
+            let asteroids = Asteroids::<T>::iter_prefix(game)
+                .map(|(coord, (kind, ttl_block))| (coord, kind, ttl_block))
+                .collect();
+
+            if game != MAIN_GAME {
+                let map_size = Self::map_size(game);
+                return WorldSnapshot {
+                    map_size,
+                    topology: Self::topology(game),
+                    asteroids,
+                    base_tiles: vec![Coord {
+                        x: map_size / 2,
+                        y: map_size / 2,
+                    }],
+                    black_holes: Vec::new(),
+                    hazard_zones: Vec::new(),
+                    blocked_rows: Vec::new(),
+                    spawn_zones: Vec::new(),
+                };
+            }
+
+            WorldSnapshot {
+                map_size: Self::map_size(game),
+                topology: Self::topology(game),
+                asteroids,
+                base_tiles: BaseTiles::<T>::iter_keys().collect(),
+                black_holes: BlackHoles::<T>::get().into_inner(),
+                hazard_zones: HazardZones::<T>::get().into_inner(),
+                blocked_rows: BlockedRows::<T>::iter()
+                    .map(|(y, row)| (y, row.into_inner()))
+                    .collect(),
+                spawn_zones: SpawnZones::<T>::get().into_inner(),
+            }
+        }
+
+        /// Sets the map size. The map settings a smaller map leaves outside are cut right away,
+        /// ships and asteroids are left to `process_map_cleanup`.
+        fn resize_map(size: u32) {
+            let old_size = MapSize::<T>::get().unwrap_or(MAP_SIZE);
+            MapSize::<T>::put(size);
+
+            // A cleanup in progress restarts, since it may have been for a larger size
+            if size >= old_size && !MapCleanupProgress::<T>::exists() {
+                return;
+            }
+
+            let rows: Vec<_> = BlockedRows::<T>::iter().collect();
+            for (y, row) in rows {
+                let mut row = row.into_inner();
+                // Bytes past the map are dropped and so are the bits past it in the last byte
+                row.truncate(size.div_ceil(8) as usize);
+                if let Some(last) = row.get_mut((size / 8) as usize) {
+                    *last &= (1 << (size % 8)) - 1;
+                }
+                while row.last() == Some(&0) {
+                    row.pop();
+                }
+
+                if y >= size || row.is_empty() {
+                    BlockedRows::<T>::remove(y);
+                } else {
+                    BlockedRows::<T>::insert(y, BoundedVec::truncate_from(row));
+                }
+            }
+
+            let bases: Vec<Coord> = BaseTiles::<T>::iter_keys()
+                .filter(|base| base.x >= size || base.y >= size)
+                .collect();
+            for coord in bases {
+                BaseTiles::<T>::remove(&coord);
+                Self::deposit_event(Event::BaseTileSet {
+                    coord,
+                    enabled: false,
+                });
+            }
+
+            BlackHoles::<T>::mutate(|holes| holes.retain(|hole| hole.x < size && hole.y < size));
+            HazardZones::<T>::mutate(|zones| {
+                zones.retain(|zone| zone.min.x < size && zone.min.y < size);
+                for zone in zones.iter_mut() {
+                    zone.max = clamp_to_map(&zone.max, size);
+                }
+            });
+            SpawnZones::<T>::mutate(|zones| {
+                zones.retain(|zone| zone.min.x < size && zone.min.y < size);
+                for zone in zones.iter_mut() {
+                    zone.max = clamp_to_map(&zone.max, size);
+                }
+            });
+
+            MapCleanupProgress::<T>::put(MapCleanup {
+                size,
+                stage: MapCleanupStage::Ships,
+                cursor: None,
+            });
+        }
+
+        /// Checks the next batch of ships, asteroids, territories or stations of a map cleanup. Ships go
+        /// first, so the wrecks of ships lost outside of the map in the meantime are removed with the asteroids.
+        fn process_map_cleanup() -> Weight {
+            let mut weight = T::DbWeight::get().reads(1);
+            let Some(mut cleanup) = MapCleanupProgress::<T>::get() else {
+                return weight;
+            };
+            let size = cleanup.size;
+            let outside = |coord: &Coord| coord.x >= size || coord.y >= size;
+
+            let done = match cleanup.stage {
+                MapCleanupStage::Ships => {
+                    let mut ships = match &cleanup.cursor {
+                        Some(cursor) => ActiveShips::<T>::iter_from(cursor.to_vec()),
+                        None => ActiveShips::<T>::iter(),
+                    };
+                    let batch: Vec<_> = ships.by_ref().take(MAP_CLEANUP_BATCH).collect();
+                    cleanup.cursor = Some(BoundedVec::truncate_from(ships.last_raw_key().to_vec()));
+                    weight += T::DbWeight::get().reads(batch.len() as u64 * 3);
+
+                    for (owner, ship_id, mut ship) in batch.iter().cloned() {
+                        if ship.game != MAIN_GAME {
+                            continue;
+                        }
+                        let flight_outside = Flights::<T>::get(&owner, ship_id)
+                            .is_some_and(|flight| outside(&flight.from) || outside(&flight.to));
+                        let route_outside = Routes::<T>::get(&owner, ship_id)
+                            .is_some_and(|route| route.iter().any(&outside));
+                        if !outside(&ship.pos) && !flight_outside && !route_outside {
+                            continue;
+                        }
+
+                        let from = ship.pos.clone();
+                        ship.pos = clamp_to_map(&from, size);
+                        runtime_print!("[on_init] Ship relocated to {:?}", ship.pos);
+                        Self::deposit_event(Event::ShipRelocated {
+                            owner: owner.clone(),
+                            ship_id,
+                            from,
+                            to: ship.pos.clone(),
+                        });
+                        Flights::<T>::remove(&owner, ship_id);
+                        Routes::<T>::remove(&owner, ship_id);
+                        ActiveShips::<T>::insert(&owner, ship_id, ship);
+                        weight += T::DbWeight::get().writes(3);
+                    }
+
+                    if batch.len() < MAP_CLEANUP_BATCH {
+                        cleanup.stage = MapCleanupStage::Asteroids;
+                        cleanup.cursor = None;
+                    }
+                    false
+                }
+                MapCleanupStage::Asteroids => {
+                    let mut asteroids = match &cleanup.cursor {
+                        Some(cursor) => {
+                            Asteroids::<T>::iter_prefix_from(MAIN_GAME, cursor.to_vec())
+                        }
+                        None => Asteroids::<T>::iter_prefix(MAIN_GAME),
+                    };
+                    let batch: Vec<_> = asteroids.by_ref().take(MAP_CLEANUP_BATCH).collect();
+                    cleanup.cursor =
+                        Some(BoundedVec::truncate_from(asteroids.last_raw_key().to_vec()));
+                    weight += T::DbWeight::get().reads(batch.len() as u64);
+
+                    for (coord, (kind, _)) in batch.iter() {
+                        if outside(coord) {
+                            weight += Self::remove_asteroid(MAIN_GAME, *kind, coord.clone());
+                        }
+                    }
+
+                    if batch.len() < MAP_CLEANUP_BATCH {
+                        cleanup.stage = MapCleanupStage::Territories;
+                        cleanup.cursor = None;
+                    }
+                    false
+                }
+                MapCleanupStage::Territories => {
+                    let mut territories = match &cleanup.cursor {
+                        Some(cursor) => Territories::<T>::iter_from(cursor.to_vec()),
+                        None => Territories::<T>::iter(),
+                    };
+                    let batch: Vec<_> = territories.by_ref().take(MAP_CLEANUP_BATCH).collect();
+                    cleanup.cursor = Some(BoundedVec::truncate_from(
+                        territories.last_raw_key().to_vec(),
+                    ));
+                    weight += T::DbWeight::get().reads(batch.len() as u64);
+
+                    for (region, territory) in batch.iter().cloned() {
+                        if !outside(&region) {
+                            continue;
+                        }
+                        Territories::<T>::remove(&region);
+                        weight += Self::add_resource_to_account(
+                            &territory.owner,
+                            AsteroidKind::Gold,
+                            territory.stake,
+                        );
+                        weight += T::DbWeight::get().writes(1);
+                        Self::deposit_event(Event::TerritoryReleased {
+                            region,
+                            owner: territory.owner,
+                        });
+                    }
+
+                    if batch.len() < MAP_CLEANUP_BATCH {
+                        cleanup.stage = MapCleanupStage::Stations;
+                        cleanup.cursor = None;
+                    }
+                    false
+                }
+                MapCleanupStage::Stations => {
+                    let mut stations = match &cleanup.cursor {
+                        Some(cursor) => Stations::<T>::iter_from(cursor.to_vec()),
+                        None => Stations::<T>::iter(),
+                    };
+                    let batch: Vec<_> = stations.by_ref().take(MAP_CLEANUP_BATCH).collect();
+                    cleanup.cursor =
+                        Some(BoundedVec::truncate_from(stations.last_raw_key().to_vec()));
+                    weight += T::DbWeight::get().reads(batch.len() as u64);
+
+                    for (coord, station) in batch.iter().cloned() {
+                        if !outside(&coord) {
+                            continue;
+                        }
+                        Stations::<T>::remove(&coord);
+                        StationsCount::<T>::mutate(&station.owner, |count| {
+                            *count = count.saturating_sub(1)
+                        });
+                        weight += T::DbWeight::get().reads_writes(1, 2);
+                        Self::deposit_event(Event::StationRemoved {
+                            coord,
+                            owner: station.owner,
+                        });
+                    }
+
+                    batch.len() < MAP_CLEANUP_BATCH
+                }
+            };
+
+            if done {
+                runtime_print!("[on_init] Map cleanup finished, size: {}", size);
+                MapCleanupProgress::<T>::kill();
+                Self::deposit_event(Event::MapCleanupFinished { size });
+            } else {
+                MapCleanupProgress::<T>::put(cleanup);
+            }
+            weight += T::DbWeight::get().writes(1);
+
+            weight
+        }
+
+        fn is_valid_map_layout(layout: &MapLayout) -> bool {
+            let size = layout.size;
+            if size == 0 || size > MAX_MAP_ROWS {
+                return false;
+            }
+            let in_bounds = |coord: &Coord| coord.x < size && coord.y < size;
+
+            let mut rows: Vec<u32> = layout.blocked_rows.iter().map(|(y, _)| *y).collect();
+            rows.sort();
+            rows.dedup();
+            if rows.len() != layout.blocked_rows.len() {
+                return false;
+            }
+            // No tile outside of the map may be blocked
+            let rows_fit = layout.blocked_rows.iter().all(|(y, row)| {
+                *y < size && (size..row.len() as u32 * 8).all(|x| !is_bit_set(row, x))
+            });
+            if !rows_fit {
+                return false;
+            }
+
+            let zones_fit = layout.spawn_zones.iter().all(|zone| {
+                zone.weight > 0
+                    && zone.min.x <= zone.max.x
+                    && zone.min.y <= zone.max.y
+                    && in_bounds(&zone.max)
+                    // Wrecks are only left by ships
+                    && zone.asteroid_weights.iter().all(|(kind, _)| *kind != AsteroidKind::Wreck)
+                    && (zone.asteroid_weights.is_empty()
+                        || zone.asteroid_weights.iter().any(|(_, weight)| *weight > 0))
+            });
+            if !zones_fit {
+                return false;
+            }
+
+            layout.base_tiles.iter().all(|base| {
+                let blocked = layout
+                    .blocked_rows
+                    .iter()
+                    .any(|(y, row)| *y == base.y && is_bit_set(row, base.x));
+                in_bounds(base) && !blocked
+            })
+        }
+
+        /// Whether the grid line from `from` to `to` crosses a blocked tile. The start tile is not checked.
+        /// A destination off the map counts as blocked, so the line walk stays within the map.
+        fn is_path_blocked(game: GameId, from: &Coord, to: &Coord) -> bool {
+            if !Self::is_in_bounds(game, to) {
+                return true;
+            }
+            game == MAIN_GAME
+                && get_line(from, to, Self::wrap(game))
+                    .skip(1)
+                    .take(MAX_MAP_ROWS as usize)
+                    .any(|coord| Self::is_blocked(&coord))
+        }
+
+        /// Marks a tile as blocked or free. `coord.x` must fit into `MAX_MAP_ROW_BYTES`.
+        fn set_blocked(coord: &Coord, blocked: bool) {
+            let byte = (coord.x / 8) as usize;
+            let mut row = BlockedRows::<T>::get(coord.y)
+                .unwrap_or_default()
+                .into_inner();
+            if row.len() <= byte {
+                row.resize(byte + 1, 0);
+            }
+
+            if blocked {
+                row[byte] |= 1 << (coord.x % 8);
+            } else {
+                row[byte] &= !(1 << (coord.x % 8));
+            }
+
+            // Trailing empty bytes are dropped, and so is a row without blocked tiles
+            while row.last() == Some(&0) {
+                row.pop();
+            }
+            if row.is_empty() {
+                BlockedRows::<T>::remove(coord.y);
+            } else {
+                BlockedRows::<T>::insert(coord.y, BoundedVec::truncate_from(row));
+            }
+        }
+
+        /// Number of blocks the next flight of the ship takes, halved while a booster is active.
+        fn flight_duration(ship: &Starship<BlockNumberFor<T>>, now: BlockNumberFor<T>) -> u32 {
+            if ship.effects.is_active(PowerUp::Booster, now) {
+                (ship.stats.flight_duration / 2).max(1)
+            } else {
+                ship.stats.flight_duration
+            }
+        }
+
+        /// Claims the asteroids on the eight tiles around `coord` for a ship with an active magnet.
+        fn pull_adjacent_asteroids(
+            game: GameId,
+            owner: &UserAccount<T>,
+            ship_id: ShipId,
+            coord: &Coord,
+            flight_start: BlockNumberFor<T>,
+        ) -> Weight {
+            let mut weight = Weight::zero();
+
+            for adjacent in get_adjacent(coord, Self::wrap(game)) {
+                weight += T::DbWeight::get().reads(1);
+                if !Asteroids::<T>::contains_key(game, &adjacent) {
+                    continue;
+                }
+
+                if Self::add_claim(game, owner, ship_id, adjacent, flight_start).is_ok() {
+                    weight +=
+                        T::DbWeight::get().reads_writes(1, 1) + T::WeightInfo::resolve_claim();
+                }
+            }
+
+            weight
+        }
+
+        /// Stats of a new ship with the given upgrades and skin perks applied on top of its class stats.
+        fn new_ship_stats(
+            base: &ShipStats,
+            upgrades: &UpgradeLevels,
+            perks: &SkinPerks,
+        ) -> ShipStats {
+            ShipStats {
+                flight_duration: base
+                    .flight_duration
+                    .saturating_sub(upgrades.speed.into())
+                    .saturating_sub(perks.flight_duration_reduction)
+                    .max(1),
+                collection_radius: base
+                    .collection_radius
+                    .saturating_add(upgrades.collection_radius.into())
+                    .saturating_add(perks.collection_radius_bonus),
+                energy_depletion: base
+                    .energy_depletion
+                    .saturating_sub(upgrades.efficiency.into())
+                    .saturating_sub(perks.depletion_reduction)
+                    .max(1),
+                sweep_bonus: base.sweep_bonus,
+                cargo_capacity: base.cargo_capacity,
+            }
+        }
+
+        /// Current stats of a ship class, including root overrides.
+        pub fn class_stats(class: ShipClass) -> ShipClassStats {
+            ClassStats::<T>::get(class).unwrap_or_else(|| class.default_stats())
+        }
+
+        /// Perks of a skin from the `Skins` registry. Returns `None` for unknown skins.
+        /// Skin 0 is the default ship without perks.
+        pub fn skin_perks(nft_skin: u32) -> Option<SkinPerks> {
+            match Skins::<T>::get(nft_skin) {
+                Some(skin) => Some(skin.perks),
+                None if nft_skin == 0 => Some(SkinPerks::default()),
+                None => None,
+            }
+        }
+
+        /// Claims the asteroids lying on the grid line of a sweeping ship's flight,
+        /// at most `cap` of them. The destination itself is claimed by the regular landing.
+        fn sweep_flight_path(
+            game: GameId,
+            owner: &UserAccount<T>,
+            ship_id: ShipId,
+            flight: &Flight<BlockNumberFor<T>>,
+            cap: u32,
+        ) -> Weight {
+            let mut weight = Weight::zero();
+            let mut claimed = 0;
+
+            // A line across the largest map crosses at most `MAX_MAP_ROWS` tiles
+            for coord in
+                get_line(&flight.from, &flight.to, Self::wrap(game)).take(MAX_MAP_ROWS as usize)
+            {
+                if claimed >= cap {
+                    break;
+                }
+                if coord == flight.to {
+                    continue;
+                }
+
+                weight += T::DbWeight::get().reads(1);
+                if !Asteroids::<T>::contains_key(game, &coord) {
+                    continue;
+                }
+
+                if Self::add_claim(game, owner, ship_id, coord, flight.start).is_ok() {
+                    claimed += 1;
+                    weight +=
+                        T::DbWeight::get().reads_writes(1, 1) + T::WeightInfo::resolve_claim();
+                }
+            }
+
+            runtime_print!(
+                "[sweep_flight_path] {:?} swept {} asteroids",
+                owner,
+                claimed
+            );
+            weight
+        }
+
+        /// Registers a claim for the asteroid at `coord`.
+        /// A ship can hold only one claim per asteroid; the earliest flight start is kept.
+        fn add_claim(
+            game: GameId,
+            user: &UserAccount<T>,
+            ship_id: ShipId,
+            coord: Coord,
+            flight_start: BlockNumberFor<T>,
+        ) -> DispatchResult {
+            PendingClaims::<T>::try_mutate(game, coord, |claims| {
+                if let Some(claim) = claims
+                    .iter_mut()
+                    .find(|claim| &claim.owner == user && claim.ship_id == ship_id)
+                {
+                    claim.flight_start = claim.flight_start.min(flight_start);
+                    return Ok(());
+                }
+
+                claims
+                    .try_push(Claim {
+                        owner: user.clone(),
+                        ship_id,
+                        flight_start,
+                    })
+                    .map_err(|_| Error::<T>::TooManyClaimants.into())
+            })
+        }
+
+        /// Hands out every claimed asteroid to a single winner.
+        /// The claimant with the earliest flight start wins; ties are broken by a hash of each claimant.
+        fn resolve_claims() {
+            for (game, coord, claims) in PendingClaims::<T>::drain() {
+                if !Asteroids::<T>::contains_key(game, &coord) {
+                    runtime_print!(
+                        "[resolve_claims] Asteroid already gone at coord {:?}",
+                        coord
+                    );
+                    continue;
+                }
+
+                let Some(earliest) = claims.iter().map(|claim| claim.flight_start).min() else {
+                    continue;
+                };
+                let tied: Vec<&Claim<UserAccount<T>, BlockNumberFor<T>>> = claims
+                    .iter()
+                    .filter(|claim| claim.flight_start == earliest)
+                    .collect();
+
+                // Ranked by a key of their own, the order the claims were made in cannot pick the winner
+                let Some(winner) = tied
+                    .into_iter()
+                    .min_by_key(|claim| get_keyed_hash::<T, _>((&claim.owner, claim.ship_id)))
+                    .cloned()
+                else {
+                    continue;
+                };
+
+                if claims.len() > 1 {
+                    Self::deposit_event(Event::AsteroidContested {
+                        game,
+                        coord: coord.clone(),
+                        contenders: BoundedVec::truncate_from(
+                            claims
+                                .iter()
+                                .map(|claim| (claim.owner.clone(), claim.ship_id))
+                                .collect(),
+                        ),
+                        winner: winner.owner.clone(),
+                        ship_id: winner.ship_id,
+                    });
+                }
+
+                Self::collect_asteroid::<T>(game, winner.owner, winner.ship_id, coord);
+            }
+        }
+
+        fn collect_asteroid<Runtime: Config>(
+            game: GameId,
+            user: UserAccount<T>,
+            ship_id: ShipId,
+            coord: Coord,
+        ) -> Weight {
+            let mut weight = Weight::zero();
+
+            let maybe_asteroid = Asteroids::<Runtime>::get(game, coord.clone());
+            weight += T::DbWeight::get().reads(1);
+
+            match maybe_asteroid {
+                None => {
+                    runtime_print!("[TakeAsteroid] No asteroid found at coord {:?}", coord);
+                    return weight;
+                }
+                Some(asteroid) => {
+                    let mut amount: u32 = 1;
+                    let mut depleted = true;
+
+                    if asteroid.0 == AsteroidKind::Energy {
+                        if let Some(mut ship) = ActiveShips::<T>::get(&user, ship_id) {
+                            weight += T::DbWeight::get().reads(1);
+
+                            amount = ENERGY_ASTEROID_REWARD;
+                            ship.energy = ship.energy.saturating_add(amount);
+                            ship.pos = coord.clone();
+
+                            runtime_print!(
+                                "[TakeAsteroid] Energy collected for user {:?}, new energy: {}",
+                                user,
+                                ship.energy
+                            );
+
+                            ActiveShips::<T>::insert(&user, ship_id, ship);
+                            weight += T::DbWeight::get().writes(1); // updated ship
+                        }
+                    } else if asteroid.0 == AsteroidKind::Radiation {
+                        amount = 0;
+                        if let Some(mut ship) = ActiveShips::<T>::get(&user, ship_id) {
+                            let now = <frame_system::Pallet<T>>::block_number();
+                            // The shield absorbs the radiation
+                            if !ship.effects.is_active(PowerUp::Shield, now) {
+                                amount = RADIATION_ENERGY_DRAIN.min(ship.energy);
+                                ship.energy = ship.energy.saturating_sub(amount);
+                            }
+                            runtime_print!(
+                                "[TakeAsteroid] Radiation drained {} energy from user {:?}",
+                                amount,
+                                user
+                            );
+
+                            ActiveShips::<T>::insert(&user, ship_id, ship);
+                            weight += T::DbWeight::get().reads_writes(1, 1);
+                        }
+                    } else if let Some(power_up) = asteroid.0.power_up() {
+                        if let Some(mut ship) = ActiveShips::<T>::get(&user, ship_id) {
+                            let now = <frame_system::Pallet<T>>::block_number();
+                            let until = now + POWER_UP_DURATION_BLOCKS.into();
+                            ship.effects.activate(power_up, until);
+                            ActiveShips::<T>::insert(&user, ship_id, ship);
+                            weight += T::DbWeight::get().reads_writes(1, 1);
+
+                            Self::deposit_event(Event::PowerUpActivated {
+                                owner: user.clone(),
+                                ship_id,
+                                power_up,
+                                until,
+                            });
+                        }
+                    } else if asteroid.0.is_cargo() {
+                        // Gold and DOT are loaded into the cargo hold, they reach the account in `deposit_cargo`
+                        let mut loot = match asteroid.0 {
+                            AsteroidKind::Gold => Cargo { gold: 1, dot: 0 },
+                            AsteroidKind::Wreck => {
+                                Wrecks::<T>::get(game, &coord).unwrap_or_default()
+                            }
+                            _ => Cargo {
+                                gold: 0,
+                                dot: Self::get_dot_amount(asteroid.0).into(),
+                            },
+                        };
+                        weight += T::DbWeight::get().reads(2);
+
+                        let Some(mut ship) = ActiveShips::<T>::get(&user, ship_id) else {
+                            return weight;
+                        };
+                        let space =
+                            u64::from(ship.stats.cargo_capacity).saturating_sub(ship.cargo.total());
+
+                        // Only a wreck can be looted partially, anything else has to fit into the hold
+                        if space == 0 || (asteroid.0 != AsteroidKind::Wreck && loot.total() > space)
+                        {
+                            runtime_print!("[TakeAsteroid] Cargo hold is full for user {:?}", user);
+                            return weight;
+                        }
+
+                        let mut loaded = loot.take(space);
+                        if game == MAIN_GAME && loaded.gold > 0 {
+                            let (tax, tax_weight) =
+                                Self::pay_territory_tax(&user, &coord, loaded.gold);
+                            loaded.gold -= tax;
+                            weight += tax_weight;
+                        }
+                        ship.cargo.add(&loaded);
+                        if game != MAIN_GAME && loaded.gold > 0 {
+                            weight += Self::record_match_gold(game, &user, loaded.gold);
+                        }
+                        amount = loaded.total().saturated_into();
+                        ActiveShips::<T>::insert(&user, ship_id, ship);
+                        weight += T::DbWeight::get().writes(1);
+
+                        // Whatever did not fit stays in the wreck for the next ship
+                        if !loot.is_empty() {
+                            Wrecks::<T>::insert(game, &coord, loot);
+                            weight += T::DbWeight::get().writes(1);
+                            depleted = false;
+                        }
+                    } else {
+                        weight += Self::add_resource_to_account(&user, asteroid.0, 1);
+                    }
+
+                    Self::deposit_event(Event::AsteroidCollected {
+                        game,
+                        owner: user.clone(),
+                        ship_id,
+                        coord: coord.clone(),
+                        resource: asteroid.0,
+                        amount,
+                    });
+
+                    if depleted {
+                        weight += Self::remove_asteroid(game, asteroid.0, coord.clone());
+                    }
+
+                    runtime_print!("[TakeAsteroid] Asteroid taken at coord {:?}", coord);
                 }
             }
 
-            let map_size = MapSize::<T>::get().unwrap_or(MAP_SIZE);
+            weight
+        }
 
-            let asteroids_count = Asteroids::<T>::iter().count();
+        fn remove_asteroid(game: GameId, resource_type: AsteroidKind, coord: Coord) -> Weight {
+            let mut weight = Weight::zero();
 
-            let max_asteroids_count = MaxAsteroidsCount::<T>::get().unwrap_or(MAX_ASTEROIDS_COUNT);
-            let difference =
-                max_asteroids_count.saturating_sub(asteroids_count.try_into().unwrap_or(0));
+            if matches!(
+                resource_type,
+                AsteroidKind::Dot0 | AsteroidKind::Dot1 | AsteroidKind::Dot2
+            ) {
+                let dot_amount = Self::get_dot_amount(resource_type);
 
-            let ttl_const = ASTEROID_TTL_CONST;
-            if difference > 0 {
-                for i in 0..difference {
-                    let coord: Coord = Coord {
-                        x: get_random_x::<T>(map_size, i as u32),
-                        y: get_random_y::<T>(map_size, i as u32),
-                    };
+                DotEmittedTotal::<T>::mutate(game, |total| {
+                    *total = total.saturating_sub(dot_amount as u64);
+                });
 
-                    if Asteroids::<T>::contains_key(coord.clone()) {
-                        runtime_print!("[on_init] Asteroid already exists at coord {:?}", coord);
-                        continue;
-                    }
+                weight += T::DbWeight::get().writes(1); // ✅
+            }
 
-                    // weight += T::DbWeight::get().reads(1);
+            if resource_type == AsteroidKind::Wreck {
+                Wrecks::<T>::remove(game, &coord);
+                weight += T::DbWeight::get().writes(1);
+            }
 
-                    // The pool size is the total amount of DOT deposited into the prize pool.
-                    // The DOT emission limit ratio is the maximum percentage of the pool that can be emitted as
-                    let pool_size = DotPrizePool::<T>::get();
-                    weight += T::DbWeight::get().reads(1);
-                    let dot_emitted = DotEmittedTotal::<T>::get();
-                    weight += T::DbWeight::get().reads(1);
-                    let last_nft_block = LastNftSpawnBlock::<T>::get();
-                    weight += T::DbWeight::get().reads(1);
+            Self::deposit_event(Event::AsteroidRemoved {
+                game,
+                coord: coord.clone(),
+            });
 
-                    // Calculate the number of players
-                    //let players_count = ActiveShips::<T>::iter().count() as u32;
-                    let players_count = PlayersCount::<T>::get();
-                    weight += T::DbWeight::get().reads(1);
+            runtime_print!("[on_initialize] remove coord: {:?}", coord);
 
-                    let asteroid_type = Self::get_random_asteroid_type::<T>(
-                        i,
-                        now,
-                        pool_size,
-                        dot_emitted,
-                        last_nft_block,
-                        players_count,
-                    );
+            Asteroids::<T>::remove(game, coord);
+            weight += T::DbWeight::get().writes(1); // ✅
 
-                    if matches!(
-                        asteroid_type,
-                        AsteroidKind::Dot0 | AsteroidKind::Dot1 | AsteroidKind::Dot2
-                    ) {
-                        let dot_amount = Self::get_dot_amount::<T>(asteroid_type);
+            weight
+        }
 
-                        // Add the DOT to the emitted total
-                        DotEmittedTotal::<T>::mutate(|total| {
-                            *total = total.saturating_add(dot_amount as u64);
-                        });
-                        weight += T::DbWeight::get().writes(1);
-                    } else if matches!(
-                        asteroid_type,
-                        AsteroidKind::Nft0 | AsteroidKind::Nft1 | AsteroidKind::Nft2
-                    ) {
-                        LastNftSpawnBlock::<T>::put(now);
-                        weight += T::DbWeight::get().writes(1);
-                    }
+        /// Starts the respawn cooldown of an account that lost its last ship in a game and takes one of its lives.
+        fn record_lost_ship(owner: &UserAccount<T>, now: BlockNumberFor<T>) -> Weight {
+            let cooldown = RespawnCooldownBlocks::<T>::get().unwrap_or(RESPAWN_COOLDOWN_BLOCKS);
+            RespawnAt::<T>::insert(owner, now + cooldown.into());
 
-                    let ttl_block = now + (ttl_const + i as u32).into();
+            let season = Season::<T>::get();
+            LostShips::<T>::mutate(owner, |(lost_season, lost)| {
+                if *lost_season != season {
+                    *lost_season = season;
+                    *lost = 0;
+                }
+                *lost = lost.saturating_add(1);
+            });
 
-                    Asteroids::<T>::insert(coord.clone(), (asteroid_type, ttl_block));
-                    runtime_print!(
-                        "[on_init] Asteroid #{:?} spawned at coord {:?}",
-                        asteroid_type,
-                        coord
-                    );
-                    Self::deposit_event(Event::AsteroidSpawned {
-                        resource_id: asteroid_type,
-                        coord: coord.clone(),
-                    });
+            T::DbWeight::get().reads_writes(3, 2)
+        }
 
-                    weight += T::DbWeight::get().writes(1);
+        /// Ships the account can still lose this season, `None` if lives are unlimited.
+        pub fn lives_left(who: &UserAccount<T>) -> Option<u32> {
+            let lives = LivesPerSeason::<T>::get()?;
+            let (season, lost) = LostShips::<T>::get(who);
+            if season != Season::<T>::get() {
+                return Some(lives);
+            }
+            Some(lives.saturating_sub(lost))
+        }
+
+        /// Leaves a share of the cargo of a ship that ran out of energy as a `Wreck` asteroid any ship can loot.
+        /// A wreck already lying on the tile takes the cargo in, any other asteroid there is replaced.
+        fn drop_wreck(
+            game: GameId,
+            owner: &UserAccount<T>,
+            ship_id: ShipId,
+            coord: Coord,
+            cargo: Cargo,
+            now: BlockNumberFor<T>,
+        ) -> Weight {
+            let mut weight = T::DbWeight::get().reads(2);
+
+            let share =
+                WreckShare::<T>::get().unwrap_or(Percent::from_percent(WRECK_SHARE_PERCENT));
+            let cargo = Cargo {
+                gold: share * cargo.gold,
+                dot: share * cargo.dot,
+            };
+            if cargo.is_empty() {
+                runtime_print!("[drop_wreck] Nothing left of the cargo of {:?}", owner);
+                return weight;
+            }
+
+            let mut wreck = cargo.clone();
+
+            match Asteroids::<T>::get(game, &coord) {
+                Some((AsteroidKind::Wreck, _)) => {
+                    wreck.add(&Wrecks::<T>::get(game, &coord).unwrap_or_default());
+                    weight += T::DbWeight::get().reads(1);
                 }
+                Some((kind, _)) => weight += Self::remove_asteroid(game, kind, coord.clone()),
+                None => {}
             }
 
-            weight += T::DbWeight::get().writes(1);
+            let ttl = WreckTtl::<T>::get().unwrap_or(WRECK_TTL_BLOCKS);
+            let ttl_block = now + ttl.into();
+            Asteroids::<T>::insert(game, &coord, (AsteroidKind::Wreck, ttl_block));
+            Wrecks::<T>::insert(game, &coord, wreck);
+            weight += T::DbWeight::get().writes(2);
+            runtime_print!("[drop_wreck] Wreck of {:?} dropped at {:?}", owner, coord);
+
+            Self::deposit_event(Event::AsteroidSpawned {
+                game,
+                resource_id: AsteroidKind::Wreck,
+                coord: coord.clone(),
+            });
+            Self::deposit_event(Event::WreckDropped {
+                game,
+                owner: owner.clone(),
+                ship_id,
+                coord,
+                cargo,
+            });
 
-            for (owner, ship) in ActiveShips::<T>::iter() {
-                let new_energy = ship.energy.saturating_sub(ENERGY_DEPLETION_RATE);
+            weight
+        }
 
-                if new_energy == 0 {
-                    runtime_print!(
-                        "[on_init] Ship has no energy and is deactivated: {:?}",
-                        owner
-                    );
-                    ActiveShips::<T>::remove(owner.clone());
-                    weight += T::DbWeight::get().writes(1);
+        fn get_dot_amount(asteroid_type: AsteroidKind) -> u32 {
+            match asteroid_type {
+                AsteroidKind::Dot0 => 1,
+                AsteroidKind::Dot1 => 2,
+                AsteroidKind::Dot2 => 3,
+                _ => 0,
+            }
+        }
 
-                    Self::deposit_event(Event::EnergyDepleted {
-                        owner: owner.clone(),
-                    });
+        fn add_resource_to_account(
+            user: &UserAccount<T>,
+            resource_type: AsteroidType,
+            amount: u64,
+        ) -> Weight {
+            AccountResources::<T>::mutate(user, resource_type, |count| {
+                *count = count.saturating_add(amount);
+                runtime_print!(
+                "[add_resource_to_account] Added {} of resource {:?} to user {:?}. Total now: {}",
+                amount, resource_type, user, *count
+            );
+            });
 
-                    PlayersCount::<T>::mutate(|player_count| {
-                        *player_count = player_count.saturating_sub(1);
-                    });
-                    weight += T::DbWeight::get().writes(1);
+            T::DbWeight::get().writes(1) // ✅
+        }
 
+        /// Checks that `creator` can open one more arena. Arenas created by accounts never close on
+        /// their own, so they cannot take the slots kept for queue matches and duels.
+        fn ensure_arena_slot(creator: &UserAccount<T>, user_arena: bool) -> DispatchResult {
+            let (mut open, mut user_open, mut created) = (0, 0, 0);
+            for (game, arena) in Arenas::<T>::iter() {
+                open += 1;
+                // The first queued player of a match does not choose to open its arena
+                if Matches::<T>::contains_key(game) {
                     continue;
                 }
+                if arena.creator == *creator {
+                    created += 1;
+                }
+                if !Duels::<T>::contains_key(game) {
+                    user_open += 1;
+                }
+            }
 
-                ActiveShips::<T>::insert(
-                    owner.clone(),
-                    Starship {
-                        pos: ship.pos,
-                        energy: new_energy,
-                        nft_skin: ship.nft_skin,
-                    },
-                );
+            if open >= MAX_ARENAS
+                || (user_arena && user_open >= MAX_USER_ARENAS)
+                || created >= MAX_ARENAS_PER_CREATOR
+            {
+                runtime_print!("[ensure_arena_slot] No arena slot left for {:?}", creator);
+                return Err(Error::<T>::TooManyArenas.into());
+            }
+            Ok(())
+        }
 
-                weight += T::DbWeight::get().writes(1);
+        /// Opens a new arena and returns its id. The caller checks the config and the arena limit.
+        fn open_arena(creator: UserAccount<T>, config: ArenaConfig) -> GameId {
+            let game = NextArenaId::<T>::get().max(MAIN_GAME + 1);
+            NextArenaId::<T>::put(game + 1);
+            Arenas::<T>::insert(
+                game,
+                Arena {
+                    creator: creator.clone(),
+                    config: config.clone(),
+                },
+            );
+
+            runtime_print!("[open_arena] Arena {} created by {:?}", game, creator);
+            Self::deposit_event(Event::ArenaCreated {
+                game,
+                creator,
+                config,
+            });
+            game
+        }
+
+        /// Starts a match for every full group of queued players, as long as arenas are available.
+        /// The first player of a group becomes the creator of the arena and can close it once it is empty.
+        fn process_match_queues() -> Weight {
+            let mut weight = Weight::zero();
+            let mut open_arenas = Arenas::<T>::iter_keys().count() as u32;
+            weight += T::DbWeight::get().reads(open_arenas as u64 + 1);
+
+            for mode in MatchMode::ALL {
+                let mut queue = MatchQueue::<T>::get(mode);
+                let players = mode.players() as usize;
+                weight += T::DbWeight::get().reads(1);
+                if queue.len() < players {
+                    continue;
+                }
+
+                // Accounts that cannot launch a ship at the moment keep their place in the queue
+                let mut started = false;
+                while open_arenas < MAX_ARENAS {
+                    let picked: Vec<usize> = queue
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, who)| Self::free_ship_slot(who).is_ok())
+                        .map(|(index, _)| index)
+                        .take(players)
+                        .collect();
+                    weight += T::DbWeight::get().reads(queue.len() as u64 * 7);
+                    if picked.len() < players {
+                        break;
+                    }
+
+                    let group: Vec<_> = picked.iter().map(|index| queue[*index].clone()).collect();
+                    let mut index = 0;
+                    queue.retain(|_| {
+                        index += 1;
+                        !picked.contains(&(index - 1))
+                    });
+                    weight += Self::start_match(mode, group);
+                    open_arenas += 1;
+                    started = true;
+                }
+
+                if started {
+                    MatchQueue::<T>::insert(mode, queue);
+                    weight += T::DbWeight::get().writes(1);
+                }
             }
 
             weight
         }
-    }
 
-    #[pallet::call]
-    impl<T: Config> Pallet<T> {
-        #[pallet::call_index(0)]
-        #[pallet::weight(T::WeightInfo::start_flight())]
-        pub fn start_flight(origin: OriginFor<T>, coord: Coord) -> DispatchResult {
-            // Check that the extrinsic was signed and get the signer.
-            let who = ensure_signed(origin)?;
+        /// Starting tiles of `count` ships at equal distance from the base in the middle of an arena.
+        fn start_positions(map_size: u32, count: u32) -> Vec<Coord> {
+            let middle = map_size / 2;
+            let radius = (map_size - 1 - middle).div_ceil(2);
+            get_start_positions(
+                &Coord {
+                    x: middle,
+                    y: middle,
+                },
+                radius,
+                count,
+            )
+        }
 
-            if Flights::<T>::contains_key(who.clone()) {
-                return Err(Error::<T>::FlightAlreadyInProgress.into());
+        /// Opens an arena for a group of queued players and places their ships
+        /// at equal distance from the base in the middle of the map.
+        fn start_match(mode: MatchMode, group: Vec<UserAccount<T>>) -> Weight {
+            let config = mode.arena_config();
+            let positions = Self::start_positions(config.map_size, group.len() as u32);
+
+            let map_size = config.map_size;
+            let game = Self::open_arena(group[0].clone(), config);
+            if mode == MatchMode::BattleRoyale {
+                Self::open_safe_zone(game, map_size);
+            }
+            let mut participants = BoundedVec::new();
+            for (who, coord) in group.into_iter().zip(positions) {
+                match Self::enter_game(who.clone(), game, coord, 0, ShipClass::default()) {
+                    Ok(()) => {
+                        let _ = participants.try_push(who);
+                    }
+                    Err(error) => {
+                        runtime_print!(
+                            "[on_init] {:?} left out of match {}: {:?}",
+                            who,
+                            game,
+                            error
+                        );
+                    }
+                }
             }
 
-            if !ActiveShips::<T>::contains_key(who.clone()) {
-                return Err(Error::<T>::NoActiveShip.into());
+            // Eligibility is checked before the queue is drained, this only guards against a solo match
+            if participants.len() < 2 {
+                runtime_print!("[on_init] Match {} has too few players and is closed", game);
+                return Self::end_match(game, &participants);
             }
 
-            let ship_coord = ActiveShips::<T>::get(who.clone()).unwrap();
-            let from_coord = ship_coord.pos.clone();
+            let end = <frame_system::Pallet<T>>::block_number() + MATCH_DURATION.into();
+            Matches::<T>::insert(
+                game,
+                Match {
+                    mode,
+                    players: participants.clone(),
+                    end,
+                },
+            );
 
-            let block_number = <frame_system::Pallet<T>>::block_number();
-            let end_block = block_number + 2u32.into();
-            Flights::<T>::insert(
-                who.clone(),
-                Flight {
-                    from: from_coord.clone(),
-                    to: coord.clone(),
-                    start: block_number.clone(),
+            runtime_print!("[on_init] Match {} started", game);
+            Self::deposit_event(Event::MatchStarted {
+                game,
+                mode,
+                participants,
+            });
+
+            T::WeightInfo::create_arena()
+                .saturating_add(T::WeightInfo::join_arena().saturating_mul(mode.players() as u64))
+        }
 
-                    end: end_block.clone(),
+        /// Places the safe zone of a battle royale arena around a random center, covering the whole map.
+        fn open_safe_zone(game: GameId, map_size: u32) {
+            let center = Coord {
+                x: get_random_x::<T>(map_size, game),
+                y: get_random_y::<T>(map_size, game),
+            };
+            let radius =
+                center.x.max(map_size - 1 - center.x) + center.y.max(map_size - 1 - center.y);
+            let next_shrink =
+                <frame_system::Pallet<T>>::block_number() + SAFE_ZONE_SHRINK_INTERVAL.into();
+            SafeZones::<T>::insert(
+                game,
+                SafeZone {
+                    center,
+                    radius,
+                    next_shrink,
                 },
             );
-            runtime_print!("[on_init] Flight added {:?}", who);
+        }
 
-            Self::deposit_event(Event::FlightStarted {
-                owner: who.clone(),
-                from: from_coord.clone(),
-                to: coord.clone(),
-                end: end_block,
-                nft_skin: ship_coord.nft_skin,
-            });
+        /// Shrinks the safe zones that are due, removes the asteroids outside of them and ends
+        /// the battle royale matches with at most one account left.
+        fn process_safe_zones(now: BlockNumberFor<T>) -> Weight {
+            let mut weight = Weight::zero();
 
-            Ok(())
+            let zones: Vec<_> = SafeZones::<T>::iter().collect();
+            weight += T::DbWeight::get().reads(zones.len() as u64 + 1);
+            for (game, mut zone) in zones {
+                if PlayersCount::<T>::get(game) <= 1 {
+                    weight += Self::finish_battle_royale(game);
+                    continue;
+                }
+                weight += T::DbWeight::get().reads(1);
+
+                if now >= zone.next_shrink && zone.radius > 0 {
+                    zone.radius = zone.radius.saturating_sub(SAFE_ZONE_SHRINK_STEP);
+                    zone.next_shrink = now + SAFE_ZONE_SHRINK_INTERVAL.into();
+                    SafeZones::<T>::insert(game, zone.clone());
+                    weight += T::DbWeight::get().writes(1);
+
+                    Self::deposit_event(Event::SafeZoneShrunk {
+                        game,
+                        center: zone.center.clone(),
+                        radius: zone.radius,
+                    });
+                }
+
+                // Also catches asteroids spawned outside since the last shrink
+                let outside: Vec<_> = Asteroids::<T>::iter_prefix(game)
+                    .filter(|(coord, _)| !zone.contains(coord))
+                    .collect();
+                weight += T::DbWeight::get().reads(outside.len() as u64 + 1);
+                for (coord, (kind, _)) in outside {
+                    weight += Self::remove_asteroid(game, kind, coord);
+                }
+            }
+
+            weight
         }
 
-        #[pallet::call_index(1)]
-        #[pallet::weight(T::WeightInfo::try_to_collect_resource())]
-        pub fn try_to_collect_resource(origin: OriginFor<T>, coord: Coord) -> DispatchResult {
-            // Ensure the call is signed and extract the caller's account
-            let who = ensure_signed(origin)?;
+        /// Pays what is left of the prize pool of a battle royale arena to the account left standing and ends the match.
+        fn finish_battle_royale(game: GameId) -> Weight {
+            let mut weight = T::DbWeight::get().reads(1);
 
-            // The player cannot collect resources while their ship is in flight
-            if Flights::<T>::contains_key(&who) {
-                runtime_print!("[try_to_collect_resource] Ship is still in flight");
-                return Err(Error::<T>::ShipInFlight.into());
+            // A match left with a single entrant has no one to win against, it is closed once it runs out of time
+            let players = Matches::<T>::get(game)
+                .map(|running| running.players.into_inner())
+                .unwrap_or_default();
+            if players.len() < 2 {
+                return weight;
             }
 
-            // The player must have an active ship on the map
-            let ship_coord = ActiveShips::<T>::get(&who).ok_or(Error::<T>::NoActiveShip)?;
+            let winner = players
+                .iter()
+                .find(|who| Self::plays_in(who, game))
+                .cloned();
+            weight += T::DbWeight::get().reads(players.len() as u64 * MAX_FLEET_SIZE as u64);
 
-            // Calculate the Manhattan distance between the ship and the asteroid
-            let distance = get_distance(ship_coord.pos.clone(), coord.clone());
+            // The pool also backs the DOT already emitted into the arena, only the rest is paid out
+            let bonus = if winner.is_some() {
+                DotPrizePool::<T>::get(game).saturating_sub(DotEmittedTotal::<T>::get(game))
+            } else {
+                0
+            };
+            weight += T::DbWeight::get().reads(2);
+            if let Some(winner) = &winner {
+                weight += Self::add_resource_to_account(winner, AsteroidKind::Dot0, bonus);
+            }
 
-            if distance > RESOURCE_DISTANCE_LIMIT {
-                runtime_print!(
-            "[try_to_collect_resource] Too far to collect resource at coord {:?}, distance: {}",
-            coord, distance
-        );
-                return Err(Error::<T>::TooFarFromResource.into());
+            weight += Self::end_match(game, &players);
+
+            runtime_print!("[on_init] Battle royale {} finished", game);
+            Self::deposit_event(Event::BattleRoyaleFinished {
+                game,
+                winner,
+                bonus,
+            });
+            weight
+        }
+
+        /// Ends the matches started from the queue that ran out of time or have no ships left.
+        fn process_matches(now: BlockNumberFor<T>) -> Weight {
+            let mut weight = Weight::zero();
+
+            let running: Vec<_> = Matches::<T>::iter().collect();
+            weight += T::DbWeight::get().reads(running.len() as u64 * 2 + 1);
+            for (game, running) in running {
+                if now < running.end && PlayersCount::<T>::get(game) > 0 {
+                    continue;
+                }
+
+                weight += Self::end_match(game, &running.players);
+                runtime_print!("[on_init] Match {} finished", game);
+                Self::deposit_event(Event::MatchFinished { game });
             }
 
-            // Collect the asteroid (adds resource and removes asteroid)
-            Self::collect_asteroid::<T>(who.clone(), coord.clone());
-            runtime_print!(
-                "[try_to_collect_resource] Successfully collected resource at coord {:?}",
-                coord
-            );
-            Ok(())
+            weight
         }
 
-        #[pallet::call_index(2)]
-        #[pallet::weight(T::WeightInfo::start_game())]
-        pub fn start_game(origin: OriginFor<T>, coord: Coord, nft_skin: u32) -> DispatchResult {
-            // Check that the extrinsic was signed and get the signer.
-            let who = ensure_signed(origin)?;
+        /// Removes an arena together with its map. The arena must have no ships left.
+        fn remove_arena(game: GameId) {
+            Arenas::<T>::remove(game);
+            let _ = Asteroids::<T>::clear_prefix(game, u32::MAX, None);
+            let _ = Wrecks::<T>::clear_prefix(game, u32::MAX, None);
+            DotPrizePool::<T>::remove(game);
+            DotEmittedTotal::<T>::remove(game);
+            LastNftSpawnBlock::<T>::remove(game);
+            PlayersCount::<T>::remove(game);
+
+            runtime_print!("[remove_arena] Arena {} closed", game);
+            Self::deposit_event(Event::ArenaClosed { game });
+        }
+
+        /// Takes every ship out of a finished match with its cargo banked, then removes the arena.
+        fn end_match(game: GameId, owners: &[UserAccount<T>]) -> Weight {
+            let mut weight = Weight::zero();
+
+            // Only the known players of the match are looked up, not every ship of every game
+            let mut ships = Vec::new();
+            for owner in owners {
+                let fleet: Vec<_> = ActiveShips::<T>::iter_prefix(owner).collect();
+                weight += T::DbWeight::get().reads(fleet.len() as u64 + 1);
+                ships.extend(
+                    fleet
+                        .into_iter()
+                        .filter(|(_, ship)| ship.game == game)
+                        .map(|(ship_id, ship)| (owner.clone(), ship_id, ship)),
+                );
+            }
+            for (owner, ship_id, ship) in ships {
+                ActiveShips::<T>::remove(&owner, ship_id);
+                Flights::<T>::remove(&owner, ship_id);
+                Routes::<T>::remove(&owner, ship_id);
+                weight +=
+                    Self::add_resource_to_account(&owner, AsteroidKind::Gold, ship.cargo.gold);
+                weight += Self::add_resource_to_account(&owner, AsteroidKind::Dot0, ship.cargo.dot);
+                weight += T::DbWeight::get().writes(3);
+            }
+
+            Matches::<T>::remove(game);
+            SafeZones::<T>::remove(game);
+            Self::remove_arena(game);
+            weight + T::DbWeight::get().writes(9)
+        }
 
-            if ActiveShips::<T>::contains_key(who.clone()) {
-                runtime_print!("[start_game] Player already has an active ship: {:?}", who);
-                return Err(Error::<T>::PlayerAlreadyHasActiveShip.into());
+        /// Adds Gold collected in a match to the score of the account.
+        fn record_match_gold(game: GameId, who: &UserAccount<T>, gold: u64) -> Weight {
+            if Tournaments::<T>::contains_key(game) || Duels::<T>::contains_key(game) {
+                MatchScores::<T>::mutate(game, who, |score| *score = score.saturating_add(gold));
+                return T::DbWeight::get().reads_writes(1, 1);
             }
+            T::DbWeight::get().reads(1)
+        }
+
+        /// Takes Gold banked by the account as the price of a purchase.
+        fn pay_gold(who: &UserAccount<T>, cost: u64) -> DispatchResult {
+            AccountResources::<T>::try_mutate(who, AsteroidKind::Gold, |gold| -> DispatchResult {
+                if *gold < cost {
+                    runtime_print!(
+                        "[pay_gold] Player does not have enough Gold: {:?}, has only {}",
+                        who,
+                        *gold
+                    );
+                    return Err(Error::<T>::NotEnoughGold.into());
+                }
+                *gold = gold.saturating_sub(cost);
+                Ok(())
+            })
+        }
+
+        /// Visits the next batch of stations and lets the ones that are due yield. The batch continues
+        /// where the previous block stopped, so every station is reached within a few blocks.
+        fn process_stations(now: BlockNumberFor<T>) -> Weight {
+            let mut weight = T::DbWeight::get().reads(1);
 
-            let map_size = MapSize::<T>::get().unwrap_or(MAP_SIZE);
-            if coord.x >= map_size || coord.y >= map_size {
-                runtime_print!("[start_game] Coordinates are out of bounds: {:?}", coord);
-                return Err(Error::<T>::InvalidCoordinates.into());
+            let mut stations = match StationCursor::<T>::get() {
+                Some(cursor) => Stations::<T>::iter_from(cursor.to_vec()),
+                None => Stations::<T>::iter(),
+            };
+            let batch: Vec<_> = stations.by_ref().take(STATION_BATCH).collect();
+            if batch.len() < STATION_BATCH {
+                StationCursor::<T>::kill();
+            } else {
+                StationCursor::<T>::put(BoundedVec::truncate_from(
+                    stations.last_raw_key().to_vec(),
+                ));
             }
+            weight += T::DbWeight::get().reads_writes(batch.len() as u64, 1);
 
-            // ! We decided not to require players to pay with Gold for participation,
-            // ! since Gold can serve better as a leaderboard score.
-            // ! Instead, we chose to use DOT for participation and created a dedicated DOT prize pool for this purpose.
-            // ! So while the logic of paying to participate remains, the project now uses a shared
-            // ! DOT pool to spawn DOT asteroids — which we believe is a better approach than requiring
-            // ! Gold for entry.
-            // let user_gold = AccountResources::<T>::get(&who, AsteroidKind::Dot0);
-            // let user_gold = AccountResources::<T>::get(&who, AsteroidKind::Gold);
-            // if user_gold < 20 {
-            //     runtime_print!(
-            //         "[start_game] Player does not have enough Gold: {:?}, has only {}",
-            //         who,
-            //         user_gold
-            //     );
-            //     return Err(Error::<T>::NoneValue.into());
-            // }
+            for (coord, mut station) in batch {
+                if now < station.next_yield {
+                    continue;
+                }
+                station.next_yield = now + STATION_YIELD_INTERVAL.into();
+                let level = u32::from(station.level);
 
-            if nft_skin != 0 {
-                let asteroid_kind = match nft_skin {
-                    5 => AsteroidKind::Nft0,
-                    6 => AsteroidKind::Nft1,
-                    7 => AsteroidKind::Nft2,
-                    _ => {
-                        runtime_print!("[start_game] Invalid nft_skin: {}", nft_skin);
-                        return Err(Error::<T>::InvalidNftSkin.into());
+                let gold = match station.output {
+                    StationOutput::Gold => STATION_GOLD_YIELD.saturating_mul(level.into()),
+                    StationOutput::Energy => {
+                        station.stored_energy = station
+                            .stored_energy
+                            .saturating_add(STATION_ENERGY_YIELD.saturating_mul(level))
+                            .min(STATION_ENERGY_CAP.saturating_mul(level));
+                        0
                     }
                 };
+                if gold > 0 {
+                    weight +=
+                        Self::add_resource_to_account(&station.owner, AsteroidKind::Gold, gold);
+                }
 
-                let has_nft = AccountResources::<T>::get(&who, asteroid_kind) > 0;
-                if !has_nft {
-                    runtime_print!(
-                        "[start_game] Player does not have the required NFT: {:?}",
-                        asteroid_kind
-                    );
-                    return Err(Error::<T>::MissingNftForSkin.into());
+                // Ships of the owner resting on the station take the recharge and the stored energy
+                let docked: Vec<_> = ActiveShips::<T>::iter_prefix(&station.owner)
+                    .filter(|(ship_id, ship)| {
+                        ship.game == MAIN_GAME
+                            && ship.pos == coord
+                            && !Flights::<T>::contains_key(&station.owner, ship_id)
+                    })
+                    .collect();
+                weight += T::DbWeight::get().reads(MAX_FLEET_SIZE as u64 * 2);
+
+                let mut energy = 0;
+                for (ship_id, mut ship) in docked {
+                    let recharge = STATION_RECHARGE_ENERGY
+                        .saturating_mul(level)
+                        .saturating_add(core::mem::take(&mut station.stored_energy));
+                    ship.energy = ship.energy.saturating_add(recharge);
+                    energy += recharge;
+                    ActiveShips::<T>::insert(&station.owner, ship_id, ship);
+                    weight += T::DbWeight::get().writes(1);
+                }
+
+                if gold > 0 || energy > 0 {
+                    Self::deposit_event(Event::StationYielded {
+                        coord: coord.clone(),
+                        owner: station.owner.clone(),
+                        gold,
+                        energy,
+                    });
                 }
+                Stations::<T>::insert(&coord, station);
+                weight += T::DbWeight::get().writes(1);
             }
 
-            ActiveShips::<T>::insert(
-                who.clone(),
-                Starship {
-                    pos: coord.clone(),
-                    energy: DEFAULT_ENERGY,
-                    nft_skin: nft_skin,
-                },
-            );
+            weight
+        }
 
-            Self::deposit_event(Event::GameStarted {
-                owner: who.clone(),
-                coord: coord.clone(),
-                nft_skin: nft_skin,
-            });
+        /// Pays the owner of the region `coord` lies in their share of the Gold collected there by another account.
+        /// The share is rounded up, so the owner gets at least one Gold. Returns the Gold paid.
+        fn pay_territory_tax(payer: &UserAccount<T>, coord: &Coord, gold: u64) -> (u64, Weight) {
+            let region = get_region(coord, TERRITORY_SIZE);
+            let mut weight = T::DbWeight::get().reads(1);
+            let Some(territory) = Territories::<T>::get(&region) else {
+                return (0, weight);
+            };
+            if territory.owner == *payer {
+                return (0, weight);
+            }
 
-            // Add the value to the total DOT prize pool
-            DotPrizePool::<T>::mutate(|pool| {
-                *pool = pool.saturating_add(DEFAULT_DOT_STAKE);
-            });
-            runtime_print!("[on_init] Active ship added {:?} coord: {:?}", who, coord);
+            let share =
+                TerritoryTax::<T>::get().unwrap_or(Percent::from_percent(TERRITORY_TAX_PERCENT));
+            let tax = share.mul_floor(gold);
+            weight += T::DbWeight::get().reads(1);
+            if tax == 0 {
+                return (0, weight);
+            }
+            weight += Self::add_resource_to_account(&territory.owner, AsteroidKind::Gold, tax);
 
-            PlayersCount::<T>::mutate(|player_count| {
-                *player_count = player_count.saturating_add(1);
+            Self::deposit_event(Event::TerritoryTaxPaid {
+                region,
+                owner: territory.owner,
+                payer: payer.clone(),
+                gold: tax,
             });
-            Ok(())
+            (tax, weight)
         }
 
-        // ! -------------------------------------------
-        // ! Admin calls are implemented to allow faster testing of the game with different parameters.
-        #[pallet::call_index(3)]
-        #[pallet::weight(T::WeightInfo::admin_set_map_size())]
-        pub fn admin_set_map_size(origin: OriginFor<T>, size: u32) -> DispatchResult {
-            ensure_root(origin)?;
-
-            MapSize::<T>::put(size);
-            runtime_print!("[set_map_size] Map size set to: {}", size);
+        /// Takes DOT banked by the account into escrow.
+        fn escrow_dot(who: &UserAccount<T>, amount: u64) -> DispatchResult {
+            let dot = AccountResources::<T>::get(who, AsteroidKind::Dot0);
+            if dot < amount {
+                runtime_print!("[escrow_dot] Not enough DOT: {:?}", who);
+                return Err(Error::<T>::NotEnoughDot.into());
+            }
+            AccountResources::<T>::insert(who, AsteroidKind::Dot0, dot - amount);
             Ok(())
         }
 
-        #[pallet::call_index(4)]
-        #[pallet::weight(T::WeightInfo::admin_set_max_asteroids_count())]
-        pub fn admin_set_max_asteroids_count(origin: OriginFor<T>, count: u32) -> DispatchResult {
-            ensure_root(origin)?;
+        /// Refunds expired challenges and pays out the duels that ended.
+        fn process_duels(now: BlockNumberFor<T>) -> Weight {
+            let mut weight = Weight::zero();
 
-            MaxAsteroidsCount::<T>::put(count);
-            runtime_print!(
-                "[set_max_asteroids_count] Max asteroids count set to: {}",
-                count
-            );
-            Ok(())
-        }
+            // Accepted challenges are already gone from `Challenges` and are skipped
+            let due = ChallengeExpiries::<T>::take(now);
+            weight += T::DbWeight::get().reads_writes(due.len() as u64 + 1, 1);
+            for challenge_id in due {
+                let Some(challenge) = Challenges::<T>::take(challenge_id) else {
+                    continue;
+                };
+                weight += Self::add_resource_to_account(
+                    &challenge.challenger,
+                    AsteroidKind::Dot0,
+                    challenge.wager,
+                );
+                weight += T::DbWeight::get().writes(1);
+                Self::deposit_event(Event::ChallengeExpired { challenge_id });
+            }
 
-        #[pallet::call_index(5)]
-        #[pallet::weight(T::WeightInfo::admin_reset_game())]
-        pub fn admin_reset_game(origin: OriginFor<T>) -> DispatchResult {
-            ensure_root(origin)?;
+            let finished: Vec<_> = Duels::<T>::iter()
+                .filter(|(_, duel)| now >= duel.end)
+                .collect();
+            weight += T::DbWeight::get().reads(finished.len() as u64 + 1);
+            for (game, duel) in finished {
+                let challenger_gold = MatchScores::<T>::get(game, &duel.challenger);
+                let opponent_gold = MatchScores::<T>::get(game, &duel.opponent);
+                let pot = duel.wager.saturating_mul(2);
 
-            for (owner, mut ship) in ActiveShips::<T>::iter() {
-                ship.energy = DEFAULT_ENERGY;
-                ship.pos = Coord { x: 0, y: 0 };
+                let winner = match challenger_gold.cmp(&opponent_gold) {
+                    core::cmp::Ordering::Greater => Some(duel.challenger.clone()),
+                    core::cmp::Ordering::Less => Some(duel.opponent.clone()),
+                    core::cmp::Ordering::Equal => None,
+                };
+                match &winner {
+                    Some(winner) => {
+                        weight += Self::add_resource_to_account(winner, AsteroidKind::Dot0, pot);
+                    }
+                    None => {
+                        weight += Self::add_resource_to_account(
+                            &duel.challenger,
+                            AsteroidKind::Dot0,
+                            duel.wager,
+                        );
+                        weight += Self::add_resource_to_account(
+                            &duel.opponent,
+                            AsteroidKind::Dot0,
+                            duel.wager,
+                        );
+                    }
+                }
 
-                ActiveShips::<T>::insert(&owner, ship);
-                runtime_print!(
-                    "[admin_reset_game] Reset ship for {:?} to energy={} pos=(0,0)",
-                    owner,
-                    DEFAULT_ENERGY
-                );
-            }
+                Duels::<T>::remove(game);
+                let _ = MatchScores::<T>::clear_prefix(game, u32::MAX, None);
+                weight += T::DbWeight::get().reads_writes(2, 3);
+                weight += Self::end_match(game, &[duel.challenger, duel.opponent]);
 
-            for (user, _) in Flights::<T>::iter() {
-                Flights::<T>::remove(&user);
-                runtime_print!("[admin_reset_game] Cleared flight for {:?}", user);
+                runtime_print!("[on_init] Duel {} finished", game);
+                Self::deposit_event(Event::DuelFinished { game, winner, pot });
             }
-            Ok(())
+
+            weight
         }
-    }
 
-    impl<T: Config> Pallet<T> {
-        fn collect_asteroid<Runtime: Config>(user: UserAccount<T>, coord: Coord) -> Weight {
+        /// Opens the arenas of tournaments reaching their start block and pays out the ones that ended.
+        fn process_tournaments(now: BlockNumberFor<T>) -> Weight {
             let mut weight = Weight::zero();
 
-            let maybe_asteroid = Asteroids::<Runtime>::get(coord.clone());
-            weight += T::DbWeight::get().reads(1);
+            let tournaments: Vec<_> = Tournaments::<T>::iter().collect();
+            for (game, mut tournament) in tournaments {
+                weight += T::DbWeight::get().reads(1);
 
-            match maybe_asteroid {
-                None => {
-                    runtime_print!("[TakeAsteroid] No asteroid found at coord {:?}", coord);
-                    return weight;
+                if now >= tournament.end {
+                    weight += Self::finish_tournament(game, tournament);
+                } else if now >= tournament.start && !tournament.started {
+                    tournament.started = true;
+                    Tournaments::<T>::insert(game, tournament);
+                    weight += T::DbWeight::get().writes(1);
+                    runtime_print!("[on_init] Tournament {} started", game);
+                    Self::deposit_event(Event::TournamentStarted { game });
                 }
-                Some(asteroid) => {
-                    let mut amount: u32 = 1;
+            }
 
-                    if asteroid.0 == AsteroidKind::Energy {
-                        if let Some(mut ship) = ActiveShips::<T>::get(&user) {
-                            weight += T::DbWeight::get().reads(1);
+            weight
+        }
 
-                            amount = ENERGY_ASTEROID_REWARD;
-                            ship.energy = ship.energy.saturating_add(amount);
-                            ship.pos = coord.clone();
+        /// Ranks the registered accounts by the Gold collected during the match and pays out the pot.
+        /// Ties keep the registration order. Rounding leftovers and the shares of places nobody took go to the winner.
+        fn finish_tournament(
+            game: GameId,
+            tournament: Tournament<UserAccount<T>, BlockNumberFor<T>>,
+        ) -> Weight {
+            let mut weight = Weight::zero();
 
-                            runtime_print!(
-                                "[TakeAsteroid] Energy collected for user {:?}, new energy: {}",
-                                user,
-                                ship.energy
-                            );
+            let mut ranking: Vec<(UserAccount<T>, u64)> = tournament
+                .players
+                .iter()
+                .map(|who| (who.clone(), MatchScores::<T>::get(game, who)))
+                .collect();
+            weight += T::DbWeight::get().reads(ranking.len() as u64);
+            ranking.sort_by_key(|(_, score)| core::cmp::Reverse(*score));
 
-                            ActiveShips::<T>::insert(user.clone(), ship);
-                            weight += T::DbWeight::get().writes(1); // updated ship
-                        }
-                    } else if matches!(
-                        asteroid.0,
-                        AsteroidKind::Nft0
-                            | AsteroidKind::Nft1
-                            | AsteroidKind::Nft2
-                            | AsteroidKind::Gold
-                    ) {
-                        weight += Self::add_resource_to_account::<Runtime>(&user, asteroid.0, 1);
-                    } else {
-                        if matches!(
-                            asteroid.0,
-                            AsteroidKind::Dot0 | AsteroidKind::Dot1 | AsteroidKind::Dot2
-                        ) {
-                            amount = Self::get_dot_amount::<Runtime>(asteroid.0);
-                            weight += Self::add_resource_to_account::<Runtime>(
-                                &user,
-                                AsteroidKind::Dot0,
-                                amount as u64,
-                            );
-                        } else {
-                            weight += Self::add_resource_to_account::<Runtime>(
-                                &user,
-                                asteroid.0,
-                                amount as u64,
-                            );
-                        }
-                    }
+            let mut payouts: Vec<(UserAccount<T>, u64)> = ranking
+                .into_iter()
+                .zip(tournament.payout.iter())
+                .map(|((who, _), share)| (who, share.mul_floor(tournament.pot)))
+                .collect();
+            // Shares are rounded down, the first place gets what is left so the pot is paid out exactly
+            let paid: u64 = payouts.iter().map(|(_, prize)| prize).sum();
+            if let Some((_, prize)) = payouts.first_mut() {
+                *prize = prize.saturating_add(tournament.pot.saturating_sub(paid));
+            }
 
-                    Self::deposit_event(Event::AsteroidCollected {
-                        owner: user.clone(),
-                        coord: coord.clone(),
-                        resource: asteroid.0,
-                        amount,
-                    });
+            for (who, prize) in payouts.iter() {
+                weight += Self::add_resource_to_account(who, AsteroidKind::Dot0, *prize);
+            }
 
-                    weight += Self::remove_asteroid::<T>(asteroid.0, coord.clone());
+            Tournaments::<T>::remove(game);
+            let _ = MatchScores::<T>::clear_prefix(game, u32::MAX, None);
+            weight += T::DbWeight::get().writes(1 + tournament.players.len() as u64);
+            weight += Self::end_match(game, &tournament.players);
 
-                    runtime_print!("[TakeAsteroid] Asteroid taken at coord {:?}", coord);
-                }
-            }
+            runtime_print!("[on_init] Tournament {} finished", game);
+            Self::deposit_event(Event::TournamentFinished {
+                game,
+                payouts: BoundedVec::truncate_from(payouts),
+            });
 
             weight
         }
 
-        fn remove_asteroid<Runtime: Config>(resource_type: AsteroidKind, coord: Coord) -> Weight {
+        /// Removes the expired asteroids of a game and spawns new ones up to its asteroid cap.
+        fn spawn_asteroids(game: GameId, now: BlockNumberFor<T>) -> Weight {
             let mut weight = Weight::zero();
 
-            if matches!(
-                resource_type,
-                AsteroidKind::Dot0 | AsteroidKind::Dot1 | AsteroidKind::Dot2
-            ) {
-                let dot_amount = Self::get_dot_amount::<Runtime>(resource_type);
+            for (coord, (as_id, ttl_block)) in Asteroids::<T>::iter_prefix(game) {
+                // Asteroids claimed in this block are kept until the claims are resolved
+                if ttl_block < now && !PendingClaims::<T>::contains_key(game, &coord) {
+                    weight += Self::remove_asteroid(game, as_id, coord.clone());
+                }
+            }
 
-                DotEmittedTotal::<T>::mutate(|total| {
-                    *total = total.saturating_sub(dot_amount as u64);
-                });
+            let map_size = Self::map_size(game);
 
-                weight += T::DbWeight::get().writes(1); // ✅
-            }
+            let asteroids_count = Asteroids::<T>::iter_prefix(game).count();
 
-            Self::deposit_event(Event::AsteroidRemoved {
-                coord: coord.clone(),
-            });
+            let max_asteroids_count = Self::max_asteroids(game);
+            let difference =
+                max_asteroids_count.saturating_sub(asteroids_count.try_into().unwrap_or(0));
 
-            runtime_print!("[on_initialize] remove coord: {:?}", coord);
+            // Spawn zones are part of the main map, arenas spawn anywhere
+            let spawn_zones = if game == MAIN_GAME {
+                SpawnZones::<T>::get()
+            } else {
+                Default::default()
+            };
+            weight += T::DbWeight::get().reads(1);
+            let zone_weights: Vec<u32> = spawn_zones.iter().map(|zone| zone.weight).collect();
 
-            Asteroids::<T>::remove(coord);
-            weight += T::DbWeight::get().writes(1); // ✅
+            // Every arena draws from its own range of random indexes, so they do not spawn alike
+            let salt = game.saturating_mul(1_000);
 
-            weight
-        }
+            let ttl_const = ASTEROID_TTL_CONST;
+            if difference > 0 {
+                for i in 0..difference {
+                    let seed = salt.saturating_add(i);
 
-        fn get_dot_amount<Runtime: Config>(asteroid_type: AsteroidKind) -> u32 {
-            match asteroid_type {
-                AsteroidKind::Dot0 => 1,
-                AsteroidKind::Dot1 => 2,
-                AsteroidKind::Dot2 => 3,
-                _ => 0,
+                    // With spawn zones uploaded, asteroids only spawn inside them
+                    let zone =
+                        pick_weighted::<T>(&zone_weights, 700 + seed).map(|z| &spawn_zones[z]);
+                    let coord: Coord = match zone {
+                        Some(zone) => Coord {
+                            x: zone.min.x + get_random_x::<T>(zone.max.x - zone.min.x + 1, seed),
+                            y: zone.min.y + get_random_y::<T>(zone.max.y - zone.min.y + 1, seed),
+                        },
+                        None => Coord {
+                            x: get_random_x::<T>(map_size, seed),
+                            y: get_random_y::<T>(map_size, seed),
+                        },
+                    };
+
+                    if Asteroids::<T>::contains_key(game, coord.clone()) {
+                        runtime_print!("[on_init] Asteroid already exists at coord {:?}", coord);
+                        continue;
+                    }
+
+                    weight += T::DbWeight::get().reads(1);
+                    if Self::is_tile_blocked(game, &coord) {
+                        runtime_print!("[on_init] Tile is blocked at coord {:?}", coord);
+                        continue;
+                    }
+
+                    // weight += T::DbWeight::get().reads(1);
+
+                    // The pool size is the total amount of DOT deposited into the prize pool.
+                    // The DOT emission limit ratio is the maximum percentage of the pool that can be emitted as
+                    let pool_size = DotPrizePool::<T>::get(game);
+                    weight += T::DbWeight::get().reads(1);
+                    let dot_emitted = DotEmittedTotal::<T>::get(game);
+                    weight += T::DbWeight::get().reads(1);
+                    let last_nft_block = LastNftSpawnBlock::<T>::get(game);
+                    weight += T::DbWeight::get().reads(1);
+
+                    // Calculate the number of players
+                    //let players_count = ActiveShips::<T>::iter().count() as u32;
+                    let players_count = PlayersCount::<T>::get(game);
+                    weight += T::DbWeight::get().reads(1);
+
+                    let asteroid_type = match zone.filter(|zone| !zone.asteroid_weights.is_empty())
+                    {
+                        Some(zone) => Self::get_zone_asteroid_type(
+                            zone,
+                            seed,
+                            now,
+                            pool_size,
+                            dot_emitted,
+                            last_nft_block,
+                        ),
+                        None => Self::get_random_asteroid_type(
+                            seed,
+                            now,
+                            pool_size,
+                            dot_emitted,
+                            last_nft_block,
+                            players_count,
+                        ),
+                    };
+
+                    if matches!(
+                        asteroid_type,
+                        AsteroidKind::Dot0 | AsteroidKind::Dot1 | AsteroidKind::Dot2
+                    ) {
+                        let dot_amount = Self::get_dot_amount(asteroid_type);
+
+                        // Add the DOT to the emitted total
+                        DotEmittedTotal::<T>::mutate(game, |total| {
+                            *total = total.saturating_add(dot_amount as u64);
+                        });
+                        weight += T::DbWeight::get().writes(1);
+                    } else if matches!(
+                        asteroid_type,
+                        AsteroidKind::Nft0 | AsteroidKind::Nft1 | AsteroidKind::Nft2
+                    ) {
+                        LastNftSpawnBlock::<T>::insert(game, now);
+                        weight += T::DbWeight::get().writes(1);
+                    }
+
+                    let ttl_block = now + (ttl_const + i).into();
+
+                    Asteroids::<T>::insert(game, coord.clone(), (asteroid_type, ttl_block));
+                    runtime_print!(
+                        "[on_init] Asteroid #{:?} spawned at coord {:?}",
+                        asteroid_type,
+                        coord
+                    );
+                    Self::deposit_event(Event::AsteroidSpawned {
+                        game,
+                        resource_id: asteroid_type,
+                        coord: coord.clone(),
+                    });
+
+                    weight += T::DbWeight::get().writes(1);
+                }
             }
+
+            weight += T::DbWeight::get().writes(1);
+
+            weight
         }
 
-        fn add_resource_to_account<Runtime: Config>(
-            user: &UserAccount<T>,
-            resource_type: AsteroidType,
-            amount: u64,
-        ) -> Weight {
-            AccountResources::<T>::mutate(user, resource_type, |count| {
-                *count = count.saturating_add(amount);
-                runtime_print!(
-                "[add_resource_to_account] Added {} of resource {:?} to user {:?}. Total now: {}",
-                amount, resource_type, user, *count
-            );
-            });
+        /// Determines the type of asteroid to spawn in a zone with custom asteroid weights.
+        /// DOT and NFT asteroids picked while the DOT limit or the NFT cooldown applies become Gold.
+        fn get_zone_asteroid_type(
+            zone: &SpawnZone,
+            index: u32,
+            block: BlockNumberFor<T>,
+            pool_size: u64,
+            dot_emitted: u64,
+            last_nft_block: BlockNumberFor<T>,
+        ) -> AsteroidKind {
+            let weights: Vec<u32> = zone
+                .asteroid_weights
+                .iter()
+                .map(|(_, weight)| *weight)
+                .collect();
+            let Some(pick) = pick_weighted::<T>(&weights, index + 500) else {
+                return AsteroidKind::Gold;
+            };
 
-            T::DbWeight::get().writes(1) // ✅
+            match zone.asteroid_weights[pick].0 {
+                AsteroidKind::Dot0 | AsteroidKind::Dot1 | AsteroidKind::Dot2
+                    if dot_emitted >= pool_size / DOT_EMISSION_LIMIT_RATIO =>
+                {
+                    AsteroidKind::Gold
+                }
+                AsteroidKind::Nft0 | AsteroidKind::Nft1 | AsteroidKind::Nft2
+                    if block <= last_nft_block + NFT_SPAWN_COOLDOWN_BLOCKS.into() =>
+                {
+                    AsteroidKind::Gold
+                }
+                kind => kind,
+            }
         }
 
         /// Determines the type of asteroid to spawn based on randomness, DOT prize pool, NFT cooldown,
@@ -736,7 +5214,7 @@ pub mod pallet {
         ///
         /// # Returns
         /// - `AsteroidKind`: The chosen type of asteroid to spawn.
-        fn get_random_asteroid_type<Runtime: Config>(
+        fn get_random_asteroid_type(
             index: u32,
             block: BlockNumberFor<T>,
             pool_size: u64,
@@ -773,7 +5251,21 @@ pub mod pallet {
                     AsteroidKind::Nft0 // Uncommon
                 }
             }
-            // Gold asteroid: fallback default, 50% chance or when other conditions are not met.
+            // Power-up asteroid: 6% chance to spawn (roll 50–55), split evenly between the three power-ups.
+            else if (50..56).contains(&roll) {
+                if roll < 52 {
+                    AsteroidKind::Shield
+                } else if roll < 54 {
+                    AsteroidKind::Booster
+                } else {
+                    AsteroidKind::Magnet
+                }
+            }
+            // Radiation asteroid: 4% chance to spawn (roll 56–59).
+            else if roll < 60 {
+                AsteroidKind::Radiation
+            }
+            // Gold asteroid: fallback default, 40% chance or when other conditions are not met.
             else {
                 AsteroidKind::Gold
             }
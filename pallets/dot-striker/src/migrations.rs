@@ -0,0 +1,132 @@
+//! Storage migrations of the pallet.
+
+use crate::*;
+use frame_support::{
+    pallet_prelude::*,
+    storage_alias,
+    traits::{OnRuntimeUpgrade, StorageVersion},
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+
+/// Storage layout of the pallet before fleets and arenas: one ship and one flight per account,
+/// and a single game whose asteroids, player count and DOT pool were not keyed by game.
+pub mod v0 {
+    use super::*;
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+    pub struct Starship {
+        pub pos: Coord,
+        pub energy: Energy,
+        pub nft_skin: u32,
+    }
+
+    #[storage_alias]
+    pub type PlayersCount<T: Config> = StorageValue<Pallet<T>, u32, ValueQuery>;
+
+    #[storage_alias]
+    pub type Asteroids<T: Config> =
+        StorageMap<Pallet<T>, Twox64Concat, Coord, (AsteroidType, BlockNumberFor<T>), OptionQuery>;
+
+    #[storage_alias]
+    pub type Flights<T: Config> = StorageMap<
+        Pallet<T>,
+        Twox64Concat,
+        <T as frame_system::Config>::AccountId,
+        Flight<BlockNumberFor<T>>,
+        OptionQuery,
+    >;
+
+    #[storage_alias]
+    pub type ActiveShips<T: Config> = StorageMap<
+        Pallet<T>,
+        Twox64Concat,
+        <T as frame_system::Config>::AccountId,
+        Starship,
+        OptionQuery,
+    >;
+
+    #[storage_alias]
+    pub type DotPrizePool<T: Config> = StorageValue<Pallet<T>, u64, ValueQuery>;
+
+    #[storage_alias]
+    pub type DotEmittedTotal<T: Config> = StorageValue<Pallet<T>, u64, ValueQuery>;
+
+    #[storage_alias]
+    pub type LastNftSpawnBlock<T: Config> = StorageValue<Pallet<T>, BlockNumberFor<T>, ValueQuery>;
+}
+
+/// Moves the state of the single game into `MAIN_GAME` and the ship and flight of every account
+/// into slot 0 of its fleet. The ships get the stats of the standard class.
+/// Also seeds the default skins and base, which a new chain gets from its genesis.
+pub struct MigrateToV1<T>(core::marker::PhantomData<T>);
+
+impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+    fn on_runtime_upgrade() -> Weight {
+        if StorageVersion::get::<Pallet<T>>() != 0 {
+            return T::DbWeight::get().reads(1);
+        }
+
+        let mut reads = 1u64;
+        let mut writes = 1u64;
+
+        // The old and the new maps share their storage prefix, so every old entry is read before writing
+        let asteroids: Vec<_> = v0::Asteroids::<T>::drain().collect();
+        let flights: Vec<_> = v0::Flights::<T>::drain().collect();
+        let ships: Vec<_> = v0::ActiveShips::<T>::drain().collect();
+        reads += (asteroids.len() + flights.len() + ships.len()) as u64;
+        writes += (asteroids.len() + flights.len() + ships.len()) as u64 * 2;
+
+        for (coord, asteroid) in asteroids {
+            Asteroids::<T>::insert(MAIN_GAME, coord, asteroid);
+        }
+        for (owner, flight) in flights {
+            Flights::<T>::insert(owner, 0, flight);
+        }
+
+        let class = ShipClass::default();
+        let stats = Pallet::<T>::class_stats(class).stats;
+        for (owner, ship) in ships {
+            ActiveShips::<T>::insert(
+                owner,
+                0,
+                Starship {
+                    game: MAIN_GAME,
+                    pos: ship.pos,
+                    energy: ship.energy,
+                    nft_skin: ship.nft_skin,
+                    collection_mode: CollectionMode::default(),
+                    class,
+                    stats: stats.clone(),
+                    cargo: Cargo::default(),
+                    effects: Effects::default(),
+                },
+            );
+        }
+
+        PlayersCount::<T>::insert(MAIN_GAME, v0::PlayersCount::<T>::take());
+        DotPrizePool::<T>::insert(MAIN_GAME, v0::DotPrizePool::<T>::take());
+        DotEmittedTotal::<T>::insert(MAIN_GAME, v0::DotEmittedTotal::<T>::take());
+        LastNftSpawnBlock::<T>::insert(MAIN_GAME, v0::LastNftSpawnBlock::<T>::take());
+        reads += 5;
+        writes += 8;
+
+        // The NFT skins players could already pick before skins were stored on chain
+        for (skin_id, skin) in default_skins() {
+            if !Skins::<T>::contains_key(skin_id) {
+                Skins::<T>::insert(skin_id, skin);
+                writes += 1;
+            }
+            reads += 1;
+        }
+
+        // Without a base no cargo could ever be deposited
+        if BaseTiles::<T>::iter_keys().next().is_none() {
+            BaseTiles::<T>::insert(default_base(), ());
+            writes += 1;
+        }
+        reads += 1;
+
+        StorageVersion::new(1).put::<Pallet<T>>();
+        T::DbWeight::get().reads_writes(reads, writes)
+    }
+}
@@ -1,5 +1,10 @@
-use crate::{mock::*, Error, Event};
-use frame_support::{assert_noop, assert_ok};
+use crate::{mock::*, *};
+use frame_support::{
+    assert_noop, assert_ok,
+    sp_runtime::Percent,
+    traits::{OnFinalize, OnInitialize},
+    BoundedVec,
+};
 
 #[test]
 fn it_works_for_default_value() {
@@ -31,3 +36,2179 @@ fn correct_error_for_none_value() {
         // );
     });
 }
+
+fn coord(x: u32, y: u32) -> Coord {
+    Coord { x, y }
+}
+
+fn run_block(n: u64) {
+    System::set_block_number(n);
+    Template::on_initialize(n);
+    Template::on_finalize(n);
+}
+
+fn land_at(owner: u64, from: Coord, to: Coord, start: u64, end: u64) {
+    Flights::<Test>::insert(
+        owner,
+        0,
+        Flight {
+            from,
+            to,
+            start,
+            end,
+        },
+    );
+}
+
+#[test]
+fn earliest_flight_wins_contested_asteroid() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(Template::start_game(
+            RuntimeOrigin::signed(1),
+            coord(0, 0),
+            0,
+            ShipClass::Standard
+        ));
+        assert_ok!(Template::start_game(
+            RuntimeOrigin::signed(2),
+            coord(9, 9),
+            0,
+            ShipClass::Standard
+        ));
+        Asteroids::<Test>::insert(MAIN_GAME, coord(5, 5), (AsteroidKind::Gold, 100));
+
+        land_at(2, coord(9, 9), coord(5, 5), 2, 3);
+        land_at(1, coord(0, 0), coord(5, 5), 1, 3);
+        run_block(4);
+
+        assert_eq!(ActiveShips::<Test>::get(1, 0).unwrap().cargo.gold, 1);
+        assert_eq!(ActiveShips::<Test>::get(2, 0).unwrap().cargo.gold, 0);
+        assert!(!Asteroids::<Test>::contains_key(MAIN_GAME, coord(5, 5)));
+        let contested = System::events()
+            .into_iter()
+            .find_map(|record| match record.event {
+                RuntimeEvent::Template(Event::AsteroidContested {
+                    game: MAIN_GAME,
+                    coord,
+                    mut contenders,
+                    winner,
+                    ship_id,
+                }) => {
+                    contenders.sort();
+                    Some((coord, contenders.into_inner(), winner, ship_id))
+                }
+                _ => None,
+            });
+        assert_eq!(contested, Some((coord(5, 5), vec![(1, 0), (2, 0)], 1, 0)));
+    });
+}
+
+#[test]
+fn tied_flights_in_the_same_block_have_a_single_winner() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        for (who, x) in [(1, 0), (2, 9)] {
+            assert_ok!(Template::start_game(
+                RuntimeOrigin::signed(who),
+                coord(x, x),
+                0,
+                ShipClass::Standard
+            ));
+        }
+        Asteroids::<Test>::insert(MAIN_GAME, coord(5, 5), (AsteroidKind::Gold, 100));
+
+        land_at(1, coord(0, 0), coord(5, 5), 1, 3);
+        land_at(2, coord(9, 9), coord(5, 5), 1, 3);
+        run_block(4);
+
+        let gold: Vec<u64> = [1, 2]
+            .iter()
+            .map(|who| ActiveShips::<Test>::get(who, 0).unwrap().cargo.gold)
+            .collect();
+        assert!(gold == vec![1, 0] || gold == vec![0, 1]);
+        assert!(!Asteroids::<Test>::contains_key(MAIN_GAME, coord(5, 5)));
+        let winner = if gold[0] == 1 { 1 } else { 2 };
+        assert!(System::events().into_iter().any(|record| matches!(
+            record.event,
+            RuntimeEvent::Template(Event::AsteroidContested { winner: w, ref contenders, .. })
+                if w == winner && contenders.len() == 2
+        )));
+    });
+}
+
+#[test]
+fn tied_claims_are_won_regardless_of_their_order() {
+    let winner_of = |order: [u64; 2]| {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(4);
+            Asteroids::<Test>::insert(MAIN_GAME, coord(5, 5), (AsteroidKind::Gold, 100));
+            let claims: Vec<_> = order
+                .iter()
+                .map(|who| Claim {
+                    owner: *who,
+                    ship_id: 0,
+                    flight_start: 1,
+                })
+                .collect();
+            PendingClaims::<Test>::insert(
+                MAIN_GAME,
+                coord(5, 5),
+                BoundedVec::truncate_from(claims),
+            );
+            Template::on_finalize(4);
+            System::events()
+                .into_iter()
+                .find_map(|record| match record.event {
+                    RuntimeEvent::Template(Event::AsteroidContested { winner, .. }) => Some(winner),
+                    _ => None,
+                })
+        })
+    };
+
+    assert!(winner_of([1, 2]).is_some());
+    assert_eq!(winner_of([1, 2]), winner_of([2, 1]));
+}
+
+#[test]
+fn collect_call_is_resolved_at_end_of_block() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(Template::start_game(
+            RuntimeOrigin::signed(1),
+            coord(3, 3),
+            0,
+            ShipClass::Standard
+        ));
+        Asteroids::<Test>::insert(MAIN_GAME, coord(4, 4), (AsteroidKind::Gold, 100));
+
+        assert_ok!(Template::try_to_collect_resource(
+            RuntimeOrigin::signed(1),
+            0,
+            coord(4, 4)
+        ));
+        assert_eq!(ActiveShips::<Test>::get(1, 0).unwrap().cargo.gold, 0);
+        assert_eq!(PendingClaims::<Test>::get(MAIN_GAME, coord(4, 4)).len(), 1);
+
+        Template::on_finalize(1);
+        assert_eq!(ActiveShips::<Test>::get(1, 0).unwrap().cargo.gold, 1);
+        assert!(!PendingClaims::<Test>::contains_key(MAIN_GAME, coord(4, 4)));
+    });
+}
+
+#[test]
+fn collect_call_fails_when_too_far() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(Template::start_game(
+            RuntimeOrigin::signed(1),
+            coord(0, 0),
+            0,
+            ShipClass::Standard
+        ));
+
+        assert_noop!(
+            Template::try_to_collect_resource(RuntimeOrigin::signed(1), 0, coord(10, 10)),
+            Error::<Test>::TooFarFromResource
+        );
+    });
+}
+
+#[test]
+fn redirect_flight_restarts_from_interpolated_position() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(Template::start_game(
+            RuntimeOrigin::signed(1),
+            coord(0, 0),
+            0,
+            ShipClass::Standard
+        ));
+        assert_ok!(Template::start_flight(
+            RuntimeOrigin::signed(1),
+            0,
+            coord(10, 20)
+        ));
+
+        System::set_block_number(2);
+        assert_ok!(Template::redirect_flight(
+            RuntimeOrigin::signed(1),
+            0,
+            coord(0, 40)
+        ));
+
+        let ship = ActiveShips::<Test>::get(1, 0).unwrap();
+        assert_eq!(ship.pos, coord(5, 10));
+        assert_eq!(ship.energy, 90);
+        assert_eq!(
+            Flights::<Test>::get(1, 0),
+            Some(Flight {
+                from: coord(5, 10),
+                to: coord(0, 40),
+                start: 2,
+                end: 4
+            })
+        );
+        System::assert_last_event(
+            Event::FlightRedirected {
+                owner: 1,
+                ship_id: 0,
+                from: coord(5, 10),
+                to: coord(0, 40),
+                end: 4,
+                nft_skin: 0,
+                wrap: MapWrap::default(),
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn redirect_flight_requires_a_flight() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(Template::start_game(
+            RuntimeOrigin::signed(1),
+            coord(0, 0),
+            0,
+            ShipClass::Standard
+        ));
+
+        assert_noop!(
+            Template::redirect_flight(RuntimeOrigin::signed(1), 0, coord(3, 3)),
+            Error::<Test>::NoFlightInProgress
+        );
+    });
+}
+
+#[test]
+fn planned_route_is_flown_leg_by_leg() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(Template::start_game(
+            RuntimeOrigin::signed(1),
+            coord(0, 0),
+            0,
+            ShipClass::Standard
+        ));
+        Asteroids::<Test>::insert(MAIN_GAME, coord(2, 2), (AsteroidKind::Gold, 100));
+
+        let route = BoundedVec::truncate_from(vec![coord(2, 2), coord(4, 4)]);
+        assert_ok!(Template::plan_route(RuntimeOrigin::signed(1), 0, route));
+        assert_eq!(ActiveShips::<Test>::get(1, 0).unwrap().energy, 95);
+        assert_eq!(
+            Routes::<Test>::get(1, 0).unwrap().into_inner(),
+            vec![coord(4, 4)]
+        );
+
+        run_block(4);
+        System::assert_has_event(
+            Event::WaypointReached {
+                owner: 1,
+                ship_id: 0,
+                coord: coord(2, 2),
+                remaining: 1,
+            }
+            .into(),
+        );
+        assert_eq!(ActiveShips::<Test>::get(1, 0).unwrap().cargo.gold, 1);
+        assert_eq!(
+            Flights::<Test>::get(1, 0),
+            Some(Flight {
+                from: coord(2, 2),
+                to: coord(4, 4),
+                start: 4,
+                end: 6
+            })
+        );
+        assert_eq!(Routes::<Test>::get(1, 0).unwrap().len(), 0);
+
+        run_block(7);
+        System::assert_has_event(
+            Event::WaypointReached {
+                owner: 1,
+                ship_id: 0,
+                coord: coord(4, 4),
+                remaining: 0,
+            }
+            .into(),
+        );
+        assert!(Flights::<Test>::get(1, 0).is_none());
+        assert!(Routes::<Test>::get(1, 0).is_none());
+        assert_eq!(ActiveShips::<Test>::get(1, 0).unwrap().pos, coord(4, 4));
+    });
+}
+
+#[test]
+fn plan_route_rejects_empty_route() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(Template::start_game(
+            RuntimeOrigin::signed(1),
+            coord(0, 0),
+            0,
+            ShipClass::Standard
+        ));
+
+        assert_noop!(
+            Template::plan_route(RuntimeOrigin::signed(1), 0, BoundedVec::new()),
+            Error::<Test>::EmptyRoute
+        );
+    });
+}
+
+#[test]
+fn sweeping_ship_collects_along_the_path() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(Template::start_game(
+            RuntimeOrigin::signed(1),
+            coord(0, 0),
+            0,
+            ShipClass::Standard
+        ));
+        assert_ok!(Template::set_collection_mode(
+            RuntimeOrigin::signed(1),
+            0,
+            CollectionMode::Sweep
+        ));
+        assert_ok!(Template::admin_set_sweep_collect_cap(
+            RuntimeOrigin::root(),
+            2
+        ));
+        for x in [2, 3, 4, 6] {
+            Asteroids::<Test>::insert(MAIN_GAME, coord(x, 0), (AsteroidKind::Gold, 100));
+        }
+
+        land_at(1, coord(0, 0), coord(6, 0), 1, 3);
+        run_block(4);
+
+        // Two asteroids swept on the way plus the one at the destination
+        assert_eq!(ActiveShips::<Test>::get(1, 0).unwrap().cargo.gold, 3);
+        assert!(Asteroids::<Test>::contains_key(MAIN_GAME, coord(4, 0)));
+    });
+}
+
+#[test]
+fn line_covers_every_crossed_cell() {
+    assert_eq!(
+        crate::utils::get_line(&coord(0, 0), &coord(3, 1), None).collect::<Vec<_>>(),
+        vec![coord(0, 0), coord(1, 0), coord(2, 1), coord(3, 1)]
+    );
+    assert_eq!(
+        crate::utils::get_line(&coord(2, 2), &coord(2, 2), None).collect::<Vec<_>>(),
+        vec![coord(2, 2)]
+    );
+    // On a toroidal map the line crosses the edge when that is shorter
+    assert_eq!(
+        crate::utils::get_line(&coord(1, 0), &coord(8, 0), Some(10)).collect::<Vec<_>>(),
+        vec![coord(1, 0), coord(0, 0), coord(9, 0), coord(8, 0)]
+    );
+}
+
+#[test]
+fn upgrades_burn_gold_and_apply_to_new_ships() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        AccountResources::<Test>::insert(1, AsteroidKind::Gold, 45);
+
+        assert_ok!(Template::upgrade_ship(
+            RuntimeOrigin::signed(1),
+            UpgradeKind::MaxEnergy
+        ));
+        assert_ok!(Template::upgrade_ship(
+            RuntimeOrigin::signed(1),
+            UpgradeKind::CollectionRadius
+        ));
+        assert_ok!(Template::upgrade_ship(
+            RuntimeOrigin::signed(1),
+            UpgradeKind::CollectionRadius
+        ));
+        // 10 + 10 + 20 Gold
+        assert_eq!(AccountResources::<Test>::get(1, AsteroidKind::Gold), 5);
+        System::assert_last_event(
+            Event::ShipUpgraded {
+                owner: 1,
+                kind: UpgradeKind::CollectionRadius,
+                level: 2,
+                cost: 20,
+            }
+            .into(),
+        );
+
+        assert_ok!(Template::start_game(
+            RuntimeOrigin::signed(1),
+            coord(0, 0),
+            0,
+            ShipClass::Standard
+        ));
+        let ship = ActiveShips::<Test>::get(1, 0).unwrap();
+        assert_eq!(ship.energy, 120);
+        assert_eq!(ship.stats.collection_radius, 7);
+        assert_eq!(ship.stats.flight_duration, 2);
+    });
+}
+
+#[test]
+fn upgrade_fails_without_gold_or_above_max_level() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_noop!(
+            Template::upgrade_ship(RuntimeOrigin::signed(1), UpgradeKind::Speed),
+            Error::<Test>::NotEnoughGold
+        );
+
+        AccountResources::<Test>::insert(1, AsteroidKind::Gold, 100);
+        assert_ok!(Template::upgrade_ship(
+            RuntimeOrigin::signed(1),
+            UpgradeKind::Speed
+        ));
+        assert_noop!(
+            Template::upgrade_ship(RuntimeOrigin::signed(1), UpgradeKind::Speed),
+            Error::<Test>::UpgradeMaxed
+        );
+    });
+}
+
+#[test]
+fn nft_skin_perks_are_applied_to_the_ship() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        AccountResources::<Test>::insert(1, AsteroidKind::Nft2, 1);
+
+        assert_ok!(Template::start_game(
+            RuntimeOrigin::signed(1),
+            coord(0, 0),
+            7,
+            ShipClass::Standard
+        ));
+        let ship = ActiveShips::<Test>::get(1, 0).unwrap();
+        assert_eq!(ship.energy, 130);
+        assert_eq!(
+            ship.stats,
+            ShipStats {
+                flight_duration: 1,
+                collection_radius: 7,
+                energy_depletion: 1,
+                sweep_bonus: 0,
+                cargo_capacity: 10
+            }
+        );
+        assert_eq!(Template::get_skin_perks(7), Template::skin_perks(7));
+        assert_eq!(Template::get_skin_perks(42), None);
+
+        run_block(2);
+        assert_eq!(ActiveShips::<Test>::get(1, 0).unwrap().energy, 129);
+    });
+}
+
+#[test]
+fn skins_are_managed_by_root() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let skin = SkinInfo {
+            required: Some(AsteroidKind::Gold),
+            rarity: SkinRarity::Common,
+            metadata_hash: [7; 32],
+            perks: SkinPerks {
+                bonus_energy: 5,
+                ..Default::default()
+            },
+        };
+
+        assert_noop!(
+            Template::start_game(
+                RuntimeOrigin::signed(1),
+                coord(0, 0),
+                42,
+                ShipClass::Standard
+            ),
+            Error::<Test>::InvalidNftSkin
+        );
+        assert_noop!(
+            Template::admin_set_skin(RuntimeOrigin::signed(1), 42, skin.clone()),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(Template::admin_set_skin(
+            RuntimeOrigin::root(),
+            42,
+            skin.clone()
+        ));
+        assert_eq!(Template::get_skin(42), Some(skin));
+
+        assert_noop!(
+            Template::start_game(
+                RuntimeOrigin::signed(1),
+                coord(0, 0),
+                42,
+                ShipClass::Standard
+            ),
+            Error::<Test>::MissingNftForSkin
+        );
+        AccountResources::<Test>::insert(1, AsteroidKind::Gold, 1);
+        assert_ok!(Template::start_game(
+            RuntimeOrigin::signed(1),
+            coord(0, 0),
+            42,
+            ShipClass::Standard
+        ));
+        assert_eq!(ActiveShips::<Test>::get(1, 0).unwrap().energy, 105);
+
+        assert_ok!(Template::admin_remove_skin(RuntimeOrigin::root(), 42));
+        assert_noop!(
+            Template::start_game(
+                RuntimeOrigin::signed(2),
+                coord(0, 0),
+                42,
+                ShipClass::Standard
+            ),
+            Error::<Test>::InvalidNftSkin
+        );
+    });
+}
+
+#[test]
+fn ship_class_sets_base_stats() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(Template::start_game(
+            RuntimeOrigin::signed(1),
+            coord(0, 0),
+            0,
+            ShipClass::Scout
+        ));
+        let scout = ActiveShips::<Test>::get(1, 0).unwrap();
+        assert_eq!(scout.class, ShipClass::Scout);
+        assert_eq!(scout.energy, 70);
+        assert_eq!(scout.stats.flight_duration, 1);
+
+        let miner = ShipClassStats {
+            energy: 50,
+            stats: ShipStats {
+                collection_radius: 10,
+                ..Default::default()
+            },
+        };
+        assert_ok!(Template::admin_set_class_stats(
+            RuntimeOrigin::root(),
+            ShipClass::Miner,
+            miner.clone()
+        ));
+        assert_ok!(Template::start_game(
+            RuntimeOrigin::signed(2),
+            coord(0, 0),
+            0,
+            ShipClass::Miner
+        ));
+        let ship = ActiveShips::<Test>::get(2, 0).unwrap();
+        assert_eq!(ship.energy, 50);
+        assert_eq!(ship.stats, miner.stats);
+
+        assert_noop!(
+            Template::admin_set_class_stats(
+                RuntimeOrigin::root(),
+                ShipClass::Scout,
+                ShipClassStats {
+                    energy: 0,
+                    stats: Default::default()
+                }
+            ),
+            Error::<Test>::InvalidClassStats
+        );
+    });
+}
+
+#[test]
+fn account_flies_a_bounded_fleet() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        for ship_id in 0..3 {
+            assert_ok!(Template::start_game(
+                RuntimeOrigin::signed(1),
+                coord(ship_id, 0),
+                0,
+                ShipClass::Standard
+            ));
+            System::assert_last_event(
+                Event::GameStarted {
+                    game: MAIN_GAME,
+                    owner: 1,
+                    ship_id,
+                    coord: coord(ship_id, 0),
+                    nft_skin: 0,
+                    class: ShipClass::Standard,
+                }
+                .into(),
+            );
+        }
+        assert_noop!(
+            Template::start_game(
+                RuntimeOrigin::signed(1),
+                coord(0, 0),
+                0,
+                ShipClass::Standard
+            ),
+            Error::<Test>::FleetFull
+        );
+        assert_eq!(PlayersCount::<Test>::get(MAIN_GAME), 1);
+        assert_eq!(Template::get_fleet(1).len(), 3);
+
+        // Ships of the same account fly independently
+        assert_ok!(Template::start_flight(
+            RuntimeOrigin::signed(1),
+            1,
+            coord(5, 5)
+        ));
+        assert!(Flights::<Test>::get(1, 0).is_none());
+        assert!(Flights::<Test>::get(1, 1).is_some());
+        assert_noop!(
+            Template::start_flight(RuntimeOrigin::signed(1), 1, coord(6, 6)),
+            Error::<Test>::FlightAlreadyInProgress
+        );
+
+        // Losing one ship frees its slot, the account is still playing
+        ActiveShips::<Test>::mutate(1, 0, |ship| ship.as_mut().unwrap().energy = 1);
+        run_block(2);
+        System::assert_has_event(
+            Event::EnergyDepleted {
+                owner: 1,
+                ship_id: 0,
+            }
+            .into(),
+        );
+        assert_eq!(PlayersCount::<Test>::get(MAIN_GAME), 1);
+        System::set_block_number(12);
+        assert_ok!(Template::start_game(
+            RuntimeOrigin::signed(1),
+            coord(0, 0),
+            0,
+            ShipClass::Standard
+        ));
+        assert!(ActiveShips::<Test>::contains_key(1, 0));
+    });
+}
+
+#[test]
+fn cargo_is_banked_only_at_a_base() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(Template::start_game(
+            RuntimeOrigin::signed(1),
+            coord(25, 25),
+            0,
+            ShipClass::Standard
+        ));
+        Asteroids::<Test>::insert(MAIN_GAME, coord(26, 26), (AsteroidKind::Gold, 100));
+        Asteroids::<Test>::insert(MAIN_GAME, coord(24, 24), (AsteroidKind::Dot1, 100));
+
+        assert_ok!(Template::try_to_collect_resource(
+            RuntimeOrigin::signed(1),
+            0,
+            coord(26, 26)
+        ));
+        assert_ok!(Template::try_to_collect_resource(
+            RuntimeOrigin::signed(1),
+            0,
+            coord(24, 24)
+        ));
+        Template::on_finalize(1);
+        assert_eq!(
+            ActiveShips::<Test>::get(1, 0).unwrap().cargo,
+            Cargo { gold: 1, dot: 2 }
+        );
+        assert_eq!(AccountResources::<Test>::get(1, AsteroidKind::Gold), 0);
+
+        assert_ok!(Template::deposit_cargo(RuntimeOrigin::signed(1), 0));
+        assert_eq!(AccountResources::<Test>::get(1, AsteroidKind::Gold), 1);
+        assert_eq!(AccountResources::<Test>::get(1, AsteroidKind::Dot0), 2);
+        assert!(ActiveShips::<Test>::get(1, 0).unwrap().cargo.is_empty());
+        assert_noop!(
+            Template::deposit_cargo(RuntimeOrigin::signed(1), 0),
+            Error::<Test>::CargoEmpty
+        );
+
+        ActiveShips::<Test>::mutate(1, 0, |ship| {
+            let ship = ship.as_mut().unwrap();
+            ship.pos = coord(3, 3);
+            ship.cargo.gold = 10;
+        });
+        assert_noop!(
+            Template::deposit_cargo(RuntimeOrigin::signed(1), 0),
+            Error::<Test>::NotAtBase
+        );
+        Asteroids::<Test>::insert(MAIN_GAME, coord(4, 4), (AsteroidKind::Gold, 100));
+        assert_noop!(
+            Template::try_to_collect_resource(RuntimeOrigin::signed(1), 0, coord(4, 4)),
+            Error::<Test>::CargoFull
+        );
+
+        assert_ok!(Template::admin_set_base_tile(
+            RuntimeOrigin::root(),
+            coord(3, 3),
+            true
+        ));
+        assert_ok!(Template::deposit_cargo(RuntimeOrigin::signed(1), 0));
+        assert_eq!(AccountResources::<Test>::get(1, AsteroidKind::Gold), 11);
+    });
+}
+
+#[test]
+fn cargo_of_a_depleted_ship_is_left_as_a_wreck() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(Template::start_game(
+            RuntimeOrigin::signed(1),
+            coord(3, 3),
+            0,
+            ShipClass::Standard
+        ));
+        assert_ok!(Template::start_game(
+            RuntimeOrigin::signed(2),
+            coord(4, 4),
+            0,
+            ShipClass::Standard
+        ));
+        ActiveShips::<Test>::mutate(1, 0, |ship| {
+            let ship = ship.as_mut().unwrap();
+            ship.energy = 1;
+            ship.cargo = Cargo { gold: 10, dot: 4 };
+        });
+        ActiveShips::<Test>::mutate(2, 0, |ship| ship.as_mut().unwrap().cargo.gold = 6);
+
+        run_block(2);
+        assert!(ActiveShips::<Test>::get(1, 0).is_none());
+        // Half of the cargo survives by default, the wreck lasts 20 blocks
+        assert_eq!(
+            Asteroids::<Test>::get(MAIN_GAME, coord(3, 3)),
+            Some((AsteroidKind::Wreck, 22))
+        );
+        assert_eq!(
+            Wrecks::<Test>::get(MAIN_GAME, coord(3, 3)),
+            Some(Cargo { gold: 5, dot: 2 })
+        );
+
+        // The hold only has room for 4 units, the rest stays in the wreck
+        System::set_block_number(3);
+        assert_ok!(Template::try_to_collect_resource(
+            RuntimeOrigin::signed(2),
+            0,
+            coord(3, 3)
+        ));
+        Template::on_finalize(3);
+        assert_eq!(
+            ActiveShips::<Test>::get(2, 0).unwrap().cargo,
+            Cargo { gold: 10, dot: 0 }
+        );
+        assert_eq!(
+            Wrecks::<Test>::get(MAIN_GAME, coord(3, 3)),
+            Some(Cargo { gold: 1, dot: 2 })
+        );
+        assert!(Asteroids::<Test>::contains_key(MAIN_GAME, coord(3, 3)));
+    });
+}
+
+#[test]
+fn wreck_share_and_ttl_are_set_by_root() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_noop!(
+            Template::admin_set_wreck_ttl(RuntimeOrigin::root(), 0),
+            Error::<Test>::InvalidWreckTtl
+        );
+        assert_ok!(Template::admin_set_wreck_ttl(RuntimeOrigin::root(), 5));
+        for who in 1..=2 {
+            assert_ok!(Template::start_game(
+                RuntimeOrigin::signed(who),
+                coord(who as u32, 0),
+                0,
+                ShipClass::Standard
+            ));
+            ActiveShips::<Test>::mutate(who, 0, |ship| {
+                let ship = ship.as_mut().unwrap();
+                ship.energy = 1;
+                ship.cargo = Cargo { gold: 3, dot: 1 };
+            });
+        }
+        // Nothing is left of a ship once the share is zero
+        assert_ok!(Template::admin_set_wreck_share(
+            RuntimeOrigin::root(),
+            Percent::zero()
+        ));
+        ActiveShips::<Test>::mutate(2, 0, |ship| ship.as_mut().unwrap().energy = 3);
+
+        run_block(2);
+        assert!(ActiveShips::<Test>::get(1, 0).is_none());
+        assert!(!Wrecks::<Test>::contains_key(MAIN_GAME, coord(1, 0)));
+
+        assert_ok!(Template::admin_set_wreck_share(
+            RuntimeOrigin::root(),
+            Percent::from_percent(100)
+        ));
+        run_block(3);
+        assert_eq!(
+            Asteroids::<Test>::get(MAIN_GAME, coord(2, 0)),
+            Some((AsteroidKind::Wreck, 8))
+        );
+        assert_eq!(
+            Wrecks::<Test>::get(MAIN_GAME, coord(2, 0)),
+            Some(Cargo { gold: 3, dot: 1 })
+        );
+
+        run_block(9);
+        assert!(!Asteroids::<Test>::contains_key(MAIN_GAME, coord(2, 0)));
+        assert!(!Wrecks::<Test>::contains_key(MAIN_GAME, coord(2, 0)));
+    });
+}
+
+#[test]
+fn lost_ship_starts_respawn_cooldown_and_takes_a_life() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(Template::admin_set_lives_per_season(
+            RuntimeOrigin::root(),
+            Some(1)
+        ));
+        assert_ok!(Template::start_game(
+            RuntimeOrigin::signed(1),
+            coord(0, 0),
+            0,
+            ShipClass::Standard
+        ));
+        assert_eq!(Template::get_lives_left(1), Some(1));
+        assert_eq!(Template::get_respawn_block(1), Some(1));
+
+        ActiveShips::<Test>::mutate(1, 0, |ship| ship.as_mut().unwrap().energy = 1);
+        run_block(2);
+        assert_eq!(Template::get_lives_left(1), Some(0));
+        assert_eq!(Template::get_respawn_block(1), None);
+
+        System::set_block_number(12);
+        assert_noop!(
+            Template::start_game(
+                RuntimeOrigin::signed(1),
+                coord(0, 0),
+                0,
+                ShipClass::Standard
+            ),
+            Error::<Test>::NoLivesLeft
+        );
+
+        // A new season restores the lives, the cooldown still has to pass
+        assert_ok!(Template::admin_start_season(RuntimeOrigin::root()));
+        assert_ok!(Template::admin_set_respawn_cooldown(
+            RuntimeOrigin::root(),
+            15
+        ));
+        assert_ok!(Template::start_game(
+            RuntimeOrigin::signed(1),
+            coord(0, 0),
+            0,
+            ShipClass::Standard
+        ));
+        ActiveShips::<Test>::mutate(1, 0, |ship| ship.as_mut().unwrap().energy = 1);
+        run_block(13);
+        assert_eq!(Template::get_respawn_block(1), None);
+
+        assert_ok!(Template::admin_set_lives_per_season(
+            RuntimeOrigin::root(),
+            None
+        ));
+        assert_eq!(Template::get_respawn_block(1), Some(28));
+        System::set_block_number(27);
+        assert_noop!(
+            Template::start_game(
+                RuntimeOrigin::signed(1),
+                coord(0, 0),
+                0,
+                ShipClass::Standard
+            ),
+            Error::<Test>::RespawnCooldown
+        );
+        System::set_block_number(28);
+        assert_ok!(Template::start_game(
+            RuntimeOrigin::signed(1),
+            coord(0, 0),
+            0,
+            ShipClass::Standard
+        ));
+
+        // Losing one ship of a fleet still flying does not stop the account from launching more
+        assert_ok!(Template::admin_set_lives_per_season(
+            RuntimeOrigin::root(),
+            Some(2)
+        ));
+        assert_ok!(Template::start_game(
+            RuntimeOrigin::signed(1),
+            coord(1, 1),
+            0,
+            ShipClass::Standard
+        ));
+        ActiveShips::<Test>::mutate(1, 0, |ship| ship.as_mut().unwrap().energy = 1);
+        run_block(29);
+        assert!(ActiveShips::<Test>::get(1, 0).is_none());
+        assert_eq!(Template::get_lives_left(1), Some(1));
+        assert_ok!(Template::start_game(
+            RuntimeOrigin::signed(1),
+            coord(0, 0),
+            0,
+            ShipClass::Standard
+        ));
+    });
+}
+
+#[test]
+fn power_ups_grant_timed_effects() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(Template::start_game(
+            RuntimeOrigin::signed(1),
+            coord(10, 10),
+            0,
+            ShipClass::Hauler
+        ));
+        for (x, kind) in [
+            (11, AsteroidKind::Shield),
+            (12, AsteroidKind::Booster),
+            (13, AsteroidKind::Magnet),
+        ] {
+            Asteroids::<Test>::insert(MAIN_GAME, coord(x, 10), (kind, 100));
+            assert_ok!(Template::try_to_collect_resource(
+                RuntimeOrigin::signed(1),
+                0,
+                coord(x, 10)
+            ));
+        }
+        Template::on_finalize(1);
+        System::assert_has_event(
+            Event::PowerUpActivated {
+                owner: 1,
+                ship_id: 0,
+                power_up: PowerUp::Shield,
+                until: 11,
+            }
+            .into(),
+        );
+
+        // The shield keeps the energy, the booster halves the flight of the Hauler
+        run_block(2);
+        assert_eq!(ActiveShips::<Test>::get(1, 0).unwrap().energy, 120);
+        assert_ok!(Template::start_flight(
+            RuntimeOrigin::signed(1),
+            0,
+            coord(30, 30)
+        ));
+        assert_eq!(Flights::<Test>::get(1, 0).unwrap().end, 3);
+
+        // The magnet pulls in everything around the landing tile
+        Asteroids::<Test>::insert(MAIN_GAME, coord(30, 30), (AsteroidKind::Gold, 100));
+        Asteroids::<Test>::insert(MAIN_GAME, coord(29, 31), (AsteroidKind::Gold, 100));
+        Asteroids::<Test>::insert(MAIN_GAME, coord(31, 30), (AsteroidKind::Gold, 100));
+        Asteroids::<Test>::insert(MAIN_GAME, coord(32, 30), (AsteroidKind::Gold, 100));
+        run_block(4);
+        assert_eq!(ActiveShips::<Test>::get(1, 0).unwrap().cargo.gold, 3);
+        assert!(Asteroids::<Test>::contains_key(MAIN_GAME, coord(32, 30)));
+
+        run_block(12);
+        System::assert_has_event(
+            Event::PowerUpExpired {
+                owner: 1,
+                ship_id: 0,
+                power_up: PowerUp::Shield,
+            }
+            .into(),
+        );
+        let ship = ActiveShips::<Test>::get(1, 0).unwrap();
+        assert_eq!(ship.effects, Effects::default());
+        assert_eq!(ship.energy, 118);
+    });
+}
+
+#[test]
+fn hazards_drain_energy_and_pull_ships() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(Template::start_game(
+            RuntimeOrigin::signed(1),
+            coord(0, 0),
+            0,
+            ShipClass::Standard
+        ));
+        Asteroids::<Test>::insert(MAIN_GAME, coord(1, 1), (AsteroidKind::Radiation, 100));
+        assert_ok!(Template::try_to_collect_resource(
+            RuntimeOrigin::signed(1),
+            0,
+            coord(1, 1)
+        ));
+        Template::on_finalize(1);
+        assert_eq!(ActiveShips::<Test>::get(1, 0).unwrap().energy, 80);
+
+        let zone = HazardZone {
+            min: coord(5, 5),
+            max: coord(10, 10),
+            extra_depletion: 3,
+        };
+        assert_noop!(
+            Template::admin_set_hazard_zones(
+                RuntimeOrigin::root(),
+                BoundedVec::truncate_from(vec![HazardZone {
+                    min: coord(10, 10),
+                    max: coord(5, 5),
+                    extra_depletion: 3,
+                }])
+            ),
+            Error::<Test>::InvalidHazardZone
+        );
+        assert_ok!(Template::admin_set_hazard_zones(
+            RuntimeOrigin::root(),
+            BoundedVec::truncate_from(vec![zone.clone()])
+        ));
+        assert_ok!(Template::admin_set_black_holes(
+            RuntimeOrigin::root(),
+            BoundedVec::truncate_from(vec![coord(9, 9)])
+        ));
+
+        // Aimed at (7, 9), the black hole pulls the ship into the zone one tile closer
+        assert_ok!(Template::start_flight(
+            RuntimeOrigin::signed(1),
+            0,
+            coord(7, 9)
+        ));
+        run_block(4);
+        System::assert_has_event(
+            Event::ShipPulled {
+                owner: 1,
+                ship_id: 0,
+                from: coord(7, 9),
+                to: coord(8, 9),
+            }
+            .into(),
+        );
+        let ship = ActiveShips::<Test>::get(1, 0).unwrap();
+        assert_eq!(ship.pos, coord(8, 9));
+        // The ship landed inside the zone, so it loses the zone's energy on top of its own
+        assert_eq!(ship.energy, 80 - 2 - 3);
+
+        let snapshot = Template::get_world_snapshot();
+        assert_eq!(snapshot.black_holes, vec![coord(9, 9)]);
+        assert_eq!(snapshot.hazard_zones, vec![zone]);
+        assert_eq!(snapshot.base_tiles, vec![coord(25, 25)]);
+        assert_eq!(
+            snapshot.asteroids.len(),
+            Asteroids::<Test>::iter_prefix(MAIN_GAME).count()
+        );
+    });
+}
+
+#[test]
+fn blocked_tiles_stop_flights_and_spawns() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let wall: Vec<Coord> = (0..50).map(|y| coord(10, y)).collect();
+        assert_ok!(Template::admin_set_blocked_tiles(
+            RuntimeOrigin::root(),
+            BoundedVec::truncate_from(wall),
+            true
+        ));
+        assert!(Template::is_blocked(&coord(10, 7)));
+        assert!(!Template::is_blocked(&coord(9, 7)));
+        assert_eq!(
+            BlockedRows::<Test>::get(7).unwrap().into_inner(),
+            vec![0, 4]
+        );
+        assert_noop!(
+            Template::admin_set_blocked_tiles(
+                RuntimeOrigin::root(),
+                BoundedVec::truncate_from(vec![coord(50, 0)]),
+                true
+            ),
+            Error::<Test>::InvalidCoordinates
+        );
+
+        assert_noop!(
+            Template::start_game(
+                RuntimeOrigin::signed(1),
+                coord(10, 3),
+                0,
+                ShipClass::Standard
+            ),
+            Error::<Test>::TileBlocked
+        );
+        assert_ok!(Template::start_game(
+            RuntimeOrigin::signed(1),
+            coord(5, 5),
+            0,
+            ShipClass::Standard
+        ));
+        assert_noop!(
+            Template::start_flight(RuntimeOrigin::signed(1), 0, coord(20, 5)),
+            Error::<Test>::PathBlocked
+        );
+        assert_noop!(
+            Template::plan_route(
+                RuntimeOrigin::signed(1),
+                0,
+                BoundedVec::truncate_from(vec![coord(5, 9), coord(12, 9)])
+            ),
+            Error::<Test>::PathBlocked
+        );
+        assert_ok!(Template::start_flight(
+            RuntimeOrigin::signed(1),
+            0,
+            coord(9, 20)
+        ));
+
+        run_block(2);
+        assert!(Asteroids::<Test>::iter_key_prefix(MAIN_GAME).all(|coord| coord.x != 10));
+        assert_eq!(Template::get_world_snapshot().blocked_rows.len(), 50);
+
+        // Clearing a row removes it from storage
+        assert_ok!(Template::admin_set_blocked_tiles(
+            RuntimeOrigin::root(),
+            BoundedVec::truncate_from(vec![coord(10, 7)]),
+            false
+        ));
+        assert!(!BlockedRows::<Test>::contains_key(7));
+    });
+}
+
+#[test]
+fn uploaded_map_layout_limits_spawns_to_its_zones() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let layout = |size: u32, blocked_row: Vec<u8>, base: Coord| MapLayout {
+            size,
+            blocked_rows: BoundedVec::truncate_from(vec![(
+                0,
+                BoundedVec::truncate_from(blocked_row),
+            )]),
+            spawn_zones: BoundedVec::truncate_from(vec![SpawnZone {
+                min: coord(2, 2),
+                max: coord(4, 4),
+                weight: 1,
+                asteroid_weights: BoundedVec::truncate_from(vec![(AsteroidKind::Energy, 1)]),
+            }]),
+            base_tiles: BoundedVec::truncate_from(vec![base]),
+        };
+
+        assert_noop!(
+            Template::admin_upload_map(RuntimeOrigin::root(), layout(0, vec![1], coord(3, 3))),
+            Error::<Test>::InvalidMapLayout
+        );
+        // Tile (20, 0) is outside of a 20x20 map
+        assert_noop!(
+            Template::admin_upload_map(
+                RuntimeOrigin::root(),
+                layout(20, vec![0, 0, 16], coord(3, 3))
+            ),
+            Error::<Test>::InvalidMapLayout
+        );
+        assert_noop!(
+            Template::admin_upload_map(RuntimeOrigin::root(), layout(20, vec![1], coord(0, 0))),
+            Error::<Test>::InvalidMapLayout
+        );
+
+        assert_ok!(Template::admin_upload_map(
+            RuntimeOrigin::root(),
+            layout(20, vec![1], coord(3, 3))
+        ));
+        System::assert_last_event(Event::MapLayoutUploaded { size: 20 }.into());
+        assert_eq!(MapSize::<Test>::get(), Some(20));
+        assert!(Template::is_blocked(&coord(0, 0)));
+        assert_eq!(Template::get_base_tiles(), vec![coord(3, 3)]);
+
+        let _ = Asteroids::<Test>::clear_prefix(MAIN_GAME, u32::MAX, None);
+        run_block(2);
+        assert!(Asteroids::<Test>::iter_prefix(MAIN_GAME).count() > 0);
+        for (coord, asteroid) in Asteroids::<Test>::iter_prefix(MAIN_GAME) {
+            assert!((2..=4).contains(&coord.x) && (2..=4).contains(&coord.y));
+            assert_eq!(asteroid.0, AsteroidKind::Energy);
+        }
+        assert_eq!(Template::get_world_snapshot().spawn_zones.len(), 1);
+    });
+}
+
+#[test]
+fn shrinking_the_map_moves_ships_and_removes_asteroids_outside() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_noop!(
+            Template::admin_set_map_size(RuntimeOrigin::root(), 0),
+            Error::<Test>::InvalidMapSize
+        );
+        assert_noop!(
+            Template::admin_set_map_size(RuntimeOrigin::root(), 257),
+            Error::<Test>::InvalidMapSize
+        );
+
+        assert_ok!(Template::start_game(
+            RuntimeOrigin::signed(1),
+            coord(45, 45),
+            0,
+            ShipClass::Standard
+        ));
+        assert_ok!(Template::start_game(
+            RuntimeOrigin::signed(2),
+            coord(5, 5),
+            0,
+            ShipClass::Standard
+        ));
+        assert_ok!(Template::start_flight(
+            RuntimeOrigin::signed(2),
+            0,
+            coord(30, 5)
+        ));
+        Asteroids::<Test>::insert(MAIN_GAME, coord(40, 40), (AsteroidKind::Gold, 100));
+        assert_ok!(Template::admin_set_blocked_tiles(
+            RuntimeOrigin::root(),
+            BoundedVec::truncate_from(vec![coord(30, 3)]),
+            true
+        ));
+        assert_ok!(Template::admin_set_base_tile(
+            RuntimeOrigin::root(),
+            coord(40, 40),
+            true
+        ));
+
+        assert_ok!(Template::admin_set_map_size(RuntimeOrigin::root(), 20));
+        System::assert_last_event(Event::MapSizeChanged { size: 20 }.into());
+        assert!(!BlockedRows::<Test>::contains_key(3));
+        assert!(!BaseTiles::<Test>::contains_key(coord(40, 40)));
+        assert_noop!(
+            Template::start_flight(RuntimeOrigin::signed(1), 0, coord(30, 30)),
+            Error::<Test>::InvalidCoordinates
+        );
+
+        run_block(2);
+        System::assert_has_event(
+            Event::ShipRelocated {
+                owner: 1,
+                ship_id: 0,
+                from: coord(45, 45),
+                to: coord(19, 19),
+            }
+            .into(),
+        );
+        assert_eq!(ActiveShips::<Test>::get(1, 0).unwrap().pos, coord(19, 19));
+        assert!(!Flights::<Test>::contains_key(2, 0));
+        assert_eq!(ActiveShips::<Test>::get(2, 0).unwrap().pos, coord(5, 5));
+        assert!(Asteroids::<Test>::contains_key(MAIN_GAME, coord(40, 40)));
+
+        run_block(3);
+        assert!(
+            Asteroids::<Test>::iter_key_prefix(MAIN_GAME).all(|coord| coord.x < 20 && coord.y < 20)
+        );
+
+        // Territories and stations are checked last
+        run_block(4);
+        run_block(5);
+        System::assert_has_event(Event::MapCleanupFinished { size: 20 }.into());
+        assert!(MapCleanupProgress::<Test>::get().is_none());
+    });
+}
+
+#[test]
+fn toroidal_map_wraps_distance_and_flights() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(Template::admin_set_map_topology(
+            RuntimeOrigin::root(),
+            MapTopology::Toroidal
+        ));
+        assert_ok!(Template::start_game(
+            RuntimeOrigin::signed(1),
+            coord(1, 25),
+            0,
+            ShipClass::Standard
+        ));
+
+        // The asteroid is two tiles away across the left edge
+        Asteroids::<Test>::insert(MAIN_GAME, coord(49, 25), (AsteroidKind::Gold, 100));
+        assert_ok!(Template::try_to_collect_resource(
+            RuntimeOrigin::signed(1),
+            0,
+            coord(49, 25)
+        ));
+
+        assert_ok!(Template::start_flight(
+            RuntimeOrigin::signed(1),
+            0,
+            coord(47, 24)
+        ));
+        System::assert_last_event(
+            Event::FlightStarted {
+                owner: 1,
+                ship_id: 0,
+                from: coord(1, 25),
+                to: coord(47, 24),
+                end: 3,
+                nft_skin: 0,
+                wrap: MapWrap { x: -1, y: 0 },
+            }
+            .into(),
+        );
+
+        // Halfway there the ship is on the other side of the edge
+        assert_eq!(
+            crate::utils::get_interpolated_position(&coord(1, 25), &coord(47, 24), 1, 2, Some(50)),
+            coord(49, 25)
+        );
+        assert_eq!(
+            crate::utils::get_distance(coord(1, 25), coord(47, 24), Some(50)),
+            5
+        );
+        assert_eq!(
+            Template::get_world_snapshot().topology,
+            MapTopology::Toroidal
+        );
+    });
+}
+
+#[test]
+fn arenas_run_their_own_game_next_to_the_main_map() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let config = ArenaConfig {
+            map_size: 10,
+            max_asteroids: 5,
+            max_players: 1,
+            topology: MapTopology::Bounded,
+        };
+        assert_noop!(
+            Template::create_arena(
+                RuntimeOrigin::signed(9),
+                ArenaConfig {
+                    map_size: 0,
+                    ..config.clone()
+                }
+            ),
+            Error::<Test>::InvalidArenaConfig
+        );
+        assert_ok!(Template::create_arena(
+            RuntimeOrigin::signed(9),
+            config.clone()
+        ));
+        System::assert_last_event(
+            Event::ArenaCreated {
+                game: 1,
+                creator: 9,
+                config: config.clone(),
+            }
+            .into(),
+        );
+        // An account can have two arenas open at a time
+        assert_ok!(Template::create_arena(
+            RuntimeOrigin::signed(9),
+            config.clone()
+        ));
+        assert_noop!(
+            Template::create_arena(RuntimeOrigin::signed(9), config),
+            Error::<Test>::TooManyArenas
+        );
+        assert_ok!(Template::close_arena(RuntimeOrigin::signed(9), 2));
+
+        assert_noop!(
+            Template::join_arena(RuntimeOrigin::signed(1), 2, coord(5, 5), 0),
+            Error::<Test>::ArenaNotFound
+        );
+        assert_noop!(
+            Template::join_arena(RuntimeOrigin::signed(1), 1, coord(20, 20), 0),
+            Error::<Test>::InvalidCoordinates
+        );
+        assert_ok!(Template::join_arena(
+            RuntimeOrigin::signed(1),
+            1,
+            coord(5, 5),
+            0
+        ));
+        // A second ship of the same account does not take another seat
+        assert_ok!(Template::join_arena(
+            RuntimeOrigin::signed(1),
+            1,
+            coord(4, 4),
+            0
+        ));
+        assert_noop!(
+            Template::join_arena(RuntimeOrigin::signed(2), 1, coord(5, 5), 0),
+            Error::<Test>::ArenaFull
+        );
+        assert_eq!(ActiveShips::<Test>::get(1, 0).unwrap().game, 1);
+        assert_eq!(PlayersCount::<Test>::get(1), 1);
+        assert_eq!(PlayersCount::<Test>::get(MAIN_GAME), 0);
+        assert_eq!(DotPrizePool::<Test>::get(1), 2 * DEFAULT_DOT_STAKE);
+        assert_eq!(DotPrizePool::<Test>::get(MAIN_GAME), 0);
+
+        run_block(2);
+        let arena_asteroids: Vec<Coord> = Asteroids::<Test>::iter_key_prefix(1).collect();
+        assert!(!arena_asteroids.is_empty() && arena_asteroids.len() <= 5);
+        assert!(arena_asteroids
+            .iter()
+            .all(|coord| coord.x < 10 && coord.y < 10));
+        assert!(Asteroids::<Test>::iter_key_prefix(MAIN_GAME).count() > 5);
+
+        // The middle of the arena map is its base
+        let mut ship = ActiveShips::<Test>::get(1, 0).unwrap();
+        ship.cargo.gold = 2;
+        ActiveShips::<Test>::insert(1, 0, ship);
+        assert_ok!(Template::deposit_cargo(RuntimeOrigin::signed(1), 0));
+        assert_eq!(
+            Template::get_arena_snapshot(1).unwrap().base_tiles,
+            vec![coord(5, 5)]
+        );
+
+        assert_noop!(
+            Template::close_arena(RuntimeOrigin::signed(2), 1),
+            Error::<Test>::NotArenaCreator
+        );
+        assert_noop!(
+            Template::close_arena(RuntimeOrigin::signed(9), 1),
+            Error::<Test>::ArenaNotEmpty
+        );
+
+        for ship_id in 0..2 {
+            let mut ship = ActiveShips::<Test>::get(1, ship_id).unwrap();
+            ship.energy = 1;
+            ActiveShips::<Test>::insert(1, ship_id, ship);
+        }
+        run_block(3);
+        assert_eq!(PlayersCount::<Test>::get(1), 0);
+
+        assert_ok!(Template::close_arena(RuntimeOrigin::signed(9), 1));
+        System::assert_last_event(Event::ArenaClosed { game: 1 }.into());
+        assert_eq!(Asteroids::<Test>::iter_key_prefix(1).count(), 0);
+        assert!(Template::get_arena_snapshot(1).is_none());
+    });
+}
+
+#[test]
+fn tournament_pays_out_the_pot_by_gold_collected() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let config = ArenaConfig {
+            map_size: 10,
+            max_asteroids: 5,
+            max_players: 3,
+            topology: MapTopology::Bounded,
+        };
+        let payout: BoundedVec<Percent, _> =
+            vec![Percent::from_percent(60), Percent::from_percent(40)]
+                .try_into()
+                .unwrap();
+        assert_noop!(
+            Template::create_tournament(
+                RuntimeOrigin::signed(9),
+                config.clone(),
+                10,
+                vec![Percent::from_percent(60)].try_into().unwrap(),
+                3,
+                5
+            ),
+            Error::<Test>::InvalidTournament
+        );
+        assert_noop!(
+            Template::create_tournament(
+                RuntimeOrigin::signed(9),
+                config.clone(),
+                10,
+                payout.clone(),
+                3,
+                u32::MAX
+            ),
+            Error::<Test>::InvalidTournament
+        );
+        assert_ok!(Template::create_tournament(
+            RuntimeOrigin::signed(9),
+            config,
+            10,
+            payout,
+            3,
+            5
+        ));
+        assert!(Tournaments::<Test>::contains_key(1));
+
+        for who in 1..=3 {
+            AccountResources::<Test>::insert(who, AsteroidKind::Dot0, 10);
+        }
+        assert_noop!(
+            Template::register_for_tournament(RuntimeOrigin::signed(4), 1),
+            Error::<Test>::NotEnoughDot
+        );
+        for who in 1..=3 {
+            assert_ok!(Template::register_for_tournament(
+                RuntimeOrigin::signed(who),
+                1
+            ));
+        }
+        assert_noop!(
+            Template::register_for_tournament(RuntimeOrigin::signed(1), 1),
+            Error::<Test>::AlreadyRegistered
+        );
+        assert_eq!(Tournaments::<Test>::get(1).unwrap().pot, 30);
+        assert_noop!(
+            Template::join_arena(RuntimeOrigin::signed(1), 1, coord(3, 3), 0),
+            Error::<Test>::TournamentNotStarted
+        );
+        assert_noop!(
+            Template::close_arena(RuntimeOrigin::signed(9), 1),
+            Error::<Test>::ArenaInTournament
+        );
+
+        run_block(3);
+        System::assert_has_event(Event::TournamentStarted { game: 1 }.into());
+        assert_noop!(
+            Template::register_for_tournament(RuntimeOrigin::signed(4), 1),
+            Error::<Test>::RegistrationClosed
+        );
+        assert_noop!(
+            Template::join_arena(RuntimeOrigin::signed(4), 1, coord(3, 3), 0),
+            Error::<Test>::NotRegistered
+        );
+        assert_ok!(Template::join_arena(
+            RuntimeOrigin::signed(3),
+            1,
+            coord(3, 3),
+            0
+        ));
+        Asteroids::<Test>::insert(1, coord(4, 4), (AsteroidKind::Gold, 100));
+        assert_ok!(Template::try_to_collect_resource(
+            RuntimeOrigin::signed(3),
+            0,
+            coord(4, 4)
+        ));
+        Template::on_finalize(3);
+        assert_eq!(MatchScores::<Test>::get(1, 3), 1);
+
+        // Accounts 1 and 2 tie, account 1 registered first
+        run_block(8);
+        System::assert_has_event(
+            Event::TournamentFinished {
+                game: 1,
+                payouts: vec![(3, 18), (1, 12)].try_into().unwrap(),
+            }
+            .into(),
+        );
+        assert_eq!(AccountResources::<Test>::get(3, AsteroidKind::Dot0), 18);
+        assert_eq!(AccountResources::<Test>::get(1, AsteroidKind::Dot0), 12);
+        assert_eq!(AccountResources::<Test>::get(2, AsteroidKind::Dot0), 0);
+        assert_eq!(AccountResources::<Test>::get(3, AsteroidKind::Gold), 1);
+        assert!(ActiveShips::<Test>::get(3, 0).is_none());
+        assert!(!Tournaments::<Test>::contains_key(1));
+        assert!(Arenas::<Test>::get(1).is_none());
+    });
+}
+
+#[test]
+fn tournament_never_pays_out_more_than_the_pot() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let config = ArenaConfig {
+            map_size: 10,
+            max_asteroids: 5,
+            max_players: 6,
+            topology: MapTopology::Bounded,
+        };
+        let payout: BoundedVec<Percent, _> = [14, 14, 14, 14, 14, 30]
+            .into_iter()
+            .map(Percent::from_percent)
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
+        assert_ok!(Template::create_tournament(
+            RuntimeOrigin::signed(9),
+            config,
+            1,
+            payout,
+            3,
+            5
+        ));
+        for who in 1..=6 {
+            AccountResources::<Test>::insert(who, AsteroidKind::Dot0, 1);
+            assert_ok!(Template::register_for_tournament(
+                RuntimeOrigin::signed(who),
+                1
+            ));
+        }
+        assert_eq!(Tournaments::<Test>::get(1).unwrap().pot, 6);
+
+        // Rounded to the nearest, five 14% shares of 6 DOT would pay 1 each on top of the 30% share
+        run_block(3);
+        run_block(8);
+        System::assert_has_event(
+            Event::TournamentFinished {
+                game: 1,
+                payouts: vec![(1, 5), (2, 0), (3, 0), (4, 0), (5, 0), (6, 1)]
+                    .try_into()
+                    .unwrap(),
+            }
+            .into(),
+        );
+        let paid: u64 = (1..=6)
+            .map(|who| AccountResources::<Test>::get(who, AsteroidKind::Dot0))
+            .sum();
+        assert_eq!(paid, 6);
+    });
+}
+
+#[test]
+fn accounts_cannot_take_the_arena_slots_kept_for_matches() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let config = MatchMode::Quick.arena_config();
+        for creator in 11..=18 {
+            assert_ok!(Template::create_arena(
+                RuntimeOrigin::signed(creator),
+                config.clone()
+            ));
+        }
+        assert_noop!(
+            Template::create_arena(RuntimeOrigin::signed(19), config),
+            Error::<Test>::TooManyArenas
+        );
+
+        for who in 1..=2 {
+            assert_ok!(Template::join_queue(
+                RuntimeOrigin::signed(who),
+                MatchMode::Quick
+            ));
+        }
+        run_block(2);
+        System::assert_has_event(
+            Event::MatchStarted {
+                game: 9,
+                mode: MatchMode::Quick,
+                participants: vec![1, 2].try_into().unwrap(),
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn queued_players_start_a_match_in_a_fresh_arena() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        for who in 1..=4 {
+            assert_ok!(Template::join_queue(
+                RuntimeOrigin::signed(who),
+                MatchMode::Quick
+            ));
+        }
+        // Account 2 cannot launch a ship for now and keeps its place in the queue
+        RespawnAt::<Test>::insert(2, 100);
+        assert_noop!(
+            Template::join_queue(RuntimeOrigin::signed(1), MatchMode::Squad),
+            Error::<Test>::AlreadyQueued
+        );
+        assert_noop!(
+            Template::leave_queue(RuntimeOrigin::signed(5), MatchMode::Quick),
+            Error::<Test>::NotQueued
+        );
+
+        run_block(2);
+        System::assert_has_event(
+            Event::MatchStarted {
+                game: 1,
+                mode: MatchMode::Quick,
+                participants: vec![1, 3].try_into().unwrap(),
+            }
+            .into(),
+        );
+        assert_eq!(
+            MatchQueue::<Test>::get(MatchMode::Quick).into_inner(),
+            vec![2, 4]
+        );
+        assert_eq!(
+            Arenas::<Test>::get(1).unwrap().config,
+            MatchMode::Quick.arena_config()
+        );
+        assert_eq!(PlayersCount::<Test>::get(1), 2);
+
+        // Both ships start at the same distance from the base in the middle of the arena
+        let middle = coord(8, 8);
+        let first = ActiveShips::<Test>::get(1, 0).unwrap();
+        let second = ActiveShips::<Test>::get(3, 0).unwrap();
+        assert_eq!((first.game, second.game), (1, 1));
+        assert_ne!(first.pos, second.pos);
+        assert_eq!(
+            get_distance(first.pos, middle.clone(), None),
+            get_distance(second.pos, middle, None)
+        );
+
+        assert_noop!(
+            Template::join_arena(RuntimeOrigin::signed(4), 1, coord(3, 3), 0),
+            Error::<Test>::NotRegistered
+        );
+        assert_noop!(
+            Template::close_arena(RuntimeOrigin::signed(1), 1),
+            Error::<Test>::ArenaInMatch
+        );
+
+        assert_ok!(Template::leave_queue(
+            RuntimeOrigin::signed(4),
+            MatchMode::Quick
+        ));
+        assert_eq!(
+            MatchQueue::<Test>::get(MatchMode::Quick).into_inner(),
+            vec![2]
+        );
+
+        // The match closes its arena once its time is up
+        Matches::<Test>::mutate(1, |running| running.as_mut().unwrap().end = 5);
+        let mut ship = ActiveShips::<Test>::get(1, 0).unwrap();
+        ship.cargo.gold = 3;
+        ActiveShips::<Test>::insert(1, 0, ship);
+        run_block(5);
+        System::assert_has_event(Event::MatchFinished { game: 1 }.into());
+        assert!(Arenas::<Test>::get(1).is_none());
+        assert!(ActiveShips::<Test>::get(1, 0).is_none());
+        assert_eq!(AccountResources::<Test>::get(1, AsteroidKind::Gold), 3);
+    });
+}
+
+#[test]
+fn duel_arena_counts_against_the_challenger() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let config = MatchMode::Quick.arena_config();
+        for _ in 0..2 {
+            assert_ok!(Template::create_arena(
+                RuntimeOrigin::signed(1),
+                config.clone()
+            ));
+        }
+        for who in 1..=2 {
+            AccountResources::<Test>::insert(who, AsteroidKind::Dot0, 10);
+        }
+        assert_ok!(Template::challenge(RuntimeOrigin::signed(1), 2, 5, 10));
+        assert_noop!(
+            Template::accept_challenge(RuntimeOrigin::signed(2), 0),
+            Error::<Test>::TooManyArenas
+        );
+
+        assert_ok!(Template::close_arena(RuntimeOrigin::signed(1), 1));
+        assert_ok!(Template::accept_challenge(RuntimeOrigin::signed(2), 0));
+    });
+}
+
+#[test]
+fn duel_pays_the_escrowed_wagers_to_the_player_with_more_gold() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        for who in 1..=3 {
+            AccountResources::<Test>::insert(who, AsteroidKind::Dot0, 10);
+        }
+        assert_noop!(
+            Template::challenge(RuntimeOrigin::signed(1), 1, 5, 10),
+            Error::<Test>::InvalidChallenge
+        );
+        assert_noop!(
+            Template::challenge(RuntimeOrigin::signed(1), 2, 5, u32::MAX),
+            Error::<Test>::InvalidChallenge
+        );
+        assert_noop!(
+            Template::challenge(RuntimeOrigin::signed(1), 2, 50, 10),
+            Error::<Test>::NotEnoughDot
+        );
+        assert_ok!(Template::challenge(RuntimeOrigin::signed(1), 2, 5, 10));
+        assert_ok!(Template::challenge(RuntimeOrigin::signed(3), 2, 5, 10));
+        assert_eq!(AccountResources::<Test>::get(1, AsteroidKind::Dot0), 5);
+        assert_eq!(ChallengeExpiries::<Test>::get(101).into_inner(), vec![0, 1]);
+
+        assert_noop!(
+            Template::accept_challenge(RuntimeOrigin::signed(3), 0),
+            Error::<Test>::NotChallengeOpponent
+        );
+        assert_ok!(Template::accept_challenge(RuntimeOrigin::signed(2), 0));
+        System::assert_last_event(
+            Event::DuelStarted {
+                game: 1,
+                challenge_id: 0,
+                challenger: 1,
+                opponent: 2,
+                end: 11,
+            }
+            .into(),
+        );
+        assert_eq!(AccountResources::<Test>::get(2, AsteroidKind::Dot0), 5);
+        assert_eq!(ActiveShips::<Test>::get(2, 0).unwrap().game, 1);
+        assert_noop!(
+            Template::join_arena(RuntimeOrigin::signed(3), 1, coord(3, 3), 0),
+            Error::<Test>::NotRegistered
+        );
+        assert_noop!(
+            Template::close_arena(RuntimeOrigin::signed(1), 1),
+            Error::<Test>::ArenaInDuel
+        );
+
+        let ship = ActiveShips::<Test>::get(2, 0).unwrap();
+        let target = coord(ship.pos.x + 1, ship.pos.y);
+        Asteroids::<Test>::insert(1, target.clone(), (AsteroidKind::Gold, 100));
+        assert_ok!(Template::try_to_collect_resource(
+            RuntimeOrigin::signed(2),
+            0,
+            target
+        ));
+        Template::on_finalize(1);
+        assert_eq!(MatchScores::<Test>::get(1, 2), 1);
+
+        run_block(11);
+        System::assert_has_event(
+            Event::DuelFinished {
+                game: 1,
+                winner: Some(2),
+                pot: 10,
+            }
+            .into(),
+        );
+        assert_eq!(AccountResources::<Test>::get(2, AsteroidKind::Dot0), 15);
+        assert_eq!(AccountResources::<Test>::get(1, AsteroidKind::Dot0), 5);
+        assert!(ActiveShips::<Test>::get(1, 0).is_none());
+        assert!(Arenas::<Test>::get(1).is_none());
+
+        // The challenge account 2 never accepted expires and refunds the wager
+        run_block(101);
+        System::assert_has_event(Event::ChallengeExpired { challenge_id: 1 }.into());
+        assert!(Challenges::<Test>::get(1).is_none());
+        assert_eq!(AccountResources::<Test>::get(3, AsteroidKind::Dot0), 10);
+    });
+}
+
+#[test]
+fn battle_royale_shrinks_its_safe_zone_until_one_ship_is_left() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let players = MatchMode::BattleRoyale.players() as u64;
+        for who in 1..=players {
+            assert_ok!(Template::join_queue(
+                RuntimeOrigin::signed(who),
+                MatchMode::BattleRoyale
+            ));
+        }
+        run_block(2);
+        assert_eq!(PlayersCount::<Test>::get(1), players as u32);
+        let zone = Template::get_safe_zone(1).unwrap();
+        assert!(ActiveShips::<Test>::iter().all(|(_, _, ship)| zone.contains(&ship.pos)));
+
+        run_block(12);
+        System::assert_has_event(
+            Event::SafeZoneShrunk {
+                game: 1,
+                center: zone.center.clone(),
+                radius: zone.radius - 2,
+            }
+            .into(),
+        );
+
+        // Only the center is left, every ship and asteroid elsewhere is outside
+        let center = zone.center.clone();
+        SafeZones::<Test>::mutate(1, |zone| zone.as_mut().unwrap().radius = 0);
+        let outside = if center.x > 0 {
+            coord(center.x - 1, center.y)
+        } else {
+            coord(1, center.y)
+        };
+        Asteroids::<Test>::insert(1, outside.clone(), (AsteroidKind::Gold, 100));
+        let mut ship = ActiveShips::<Test>::get(1, 0).unwrap();
+        ship.pos = outside.clone();
+        ActiveShips::<Test>::insert(1, 0, ship.clone());
+        run_block(13);
+        assert!(!Asteroids::<Test>::contains_key(1, outside));
+        assert_eq!(
+            ActiveShips::<Test>::get(1, 0).unwrap().energy,
+            ship.energy - ship.stats.energy_depletion - 10
+        );
+
+        // Account 2 is knocked out and cannot come back once its cooldown is over
+        let mut ship = ActiveShips::<Test>::get(2, 0).unwrap();
+        ship.energy = 1;
+        ActiveShips::<Test>::insert(2, 0, ship);
+        run_block(14);
+        assert!(ActiveShips::<Test>::get(2, 0).is_none());
+        RespawnAt::<Test>::remove(2);
+        assert_noop!(
+            Template::join_arena(RuntimeOrigin::signed(2), 1, center.clone(), 0),
+            Error::<Test>::MatchAlreadyStarted
+        );
+
+        // Everyone else but account 1 runs out of energy
+        for who in 3..=players {
+            let mut ship = ActiveShips::<Test>::get(who, 0).unwrap();
+            ship.energy = 1;
+            ActiveShips::<Test>::insert(who, 0, ship);
+        }
+        let pool = DotPrizePool::<Test>::get(1);
+        let dot = AccountResources::<Test>::get(1, AsteroidKind::Dot0);
+        run_block(15);
+        let bonus = System::events()
+            .into_iter()
+            .find_map(|record| match record.event {
+                RuntimeEvent::Template(Event::BattleRoyaleFinished {
+                    game: 1,
+                    winner: Some(1),
+                    bonus,
+                }) => Some(bonus),
+                _ => None,
+            })
+            .expect("the battle royale is finished");
+        // The DOT asteroids still in the arena are backed by the pool and are not paid twice
+        assert!(bonus < pool);
+        assert_eq!(
+            AccountResources::<Test>::get(1, AsteroidKind::Dot0),
+            dot + bonus
+        );
+        assert!(Template::get_safe_zone(1).is_none());
+        assert!(Arenas::<Test>::get(1).is_none());
+    });
+}
+
+#[test]
+fn territory_owner_taxes_gold_collected_in_their_region() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        AccountResources::<Test>::insert(1, AsteroidKind::Gold, 20);
+        AccountResources::<Test>::insert(2, AsteroidKind::Gold, 20);
+
+        assert_noop!(
+            Template::claim_tile(RuntimeOrigin::signed(1), coord(10, 12), 5),
+            Error::<Test>::TerritoryStakeTooLow
+        );
+        assert_ok!(Template::claim_tile(
+            RuntimeOrigin::signed(1),
+            coord(10, 12),
+            10
+        ));
+        assert_eq!(Territories::<Test>::get(coord(8, 8)).unwrap().owner, 1);
+        assert_noop!(
+            Template::claim_tile(RuntimeOrigin::signed(1), coord(9, 9), 15),
+            Error::<Test>::AlreadyTerritoryOwner
+        );
+        assert_noop!(
+            Template::claim_tile(RuntimeOrigin::signed(2), coord(15, 15), 10),
+            Error::<Test>::TerritoryStakeTooLow
+        );
+
+        // Account 2 takes the region over, account 1 gets the stake back
+        assert_ok!(Template::claim_tile(
+            RuntimeOrigin::signed(2),
+            coord(15, 15),
+            12
+        ));
+        System::assert_last_event(
+            Event::TerritoryClaimed {
+                region: coord(8, 8),
+                owner: 2,
+                stake: 12,
+                previous_owner: Some(1),
+            }
+            .into(),
+        );
+        assert_eq!(AccountResources::<Test>::get(1, AsteroidKind::Gold), 20);
+        assert_eq!(AccountResources::<Test>::get(2, AsteroidKind::Gold), 8);
+        assert_eq!(
+            Template::get_territories(),
+            vec![(
+                coord(8, 8),
+                Territory {
+                    owner: 2,
+                    stake: 12
+                }
+            )]
+        );
+
+        // Account 1 collects a wreck in the region and pays a fifth of the Gold to account 2
+        assert_ok!(Template::start_game(
+            RuntimeOrigin::signed(1),
+            coord(9, 9),
+            0,
+            ShipClass::Standard
+        ));
+        Asteroids::<Test>::insert(MAIN_GAME, coord(10, 10), (AsteroidKind::Wreck, 100));
+        Wrecks::<Test>::insert(MAIN_GAME, coord(10, 10), Cargo { gold: 10, dot: 0 });
+        assert_ok!(Template::try_to_collect_resource(
+            RuntimeOrigin::signed(1),
+            0,
+            coord(10, 10)
+        ));
+        Template::on_finalize(1);
+        assert_eq!(ActiveShips::<Test>::get(1, 0).unwrap().cargo.gold, 8);
+        assert_eq!(AccountResources::<Test>::get(2, AsteroidKind::Gold), 10);
+        System::assert_has_event(
+            Event::TerritoryTaxPaid {
+                region: coord(8, 8),
+                owner: 2,
+                payer: 1,
+                gold: 2,
+            }
+            .into(),
+        );
+
+        // A single Gold is below the tax, the collector keeps it
+        Asteroids::<Test>::insert(MAIN_GAME, coord(10, 9), (AsteroidKind::Gold, 100));
+        assert_ok!(Template::try_to_collect_resource(
+            RuntimeOrigin::signed(1),
+            0,
+            coord(10, 9)
+        ));
+        Template::on_finalize(1);
+        assert_eq!(ActiveShips::<Test>::get(1, 0).unwrap().cargo.gold, 9);
+        assert_eq!(AccountResources::<Test>::get(2, AsteroidKind::Gold), 10);
+
+        // The region is past the edge of a shrunk map and is released
+        assert_ok!(Template::admin_set_map_size(RuntimeOrigin::root(), 8));
+        assert!(Territories::<Test>::get(coord(8, 8)).is_some());
+        for block in 2..=4 {
+            run_block(block);
+        }
+        assert!(Territories::<Test>::get(coord(8, 8)).is_none());
+        assert_eq!(AccountResources::<Test>::get(2, AsteroidKind::Gold), 22);
+    });
+}
+
+#[test]
+fn stations_yield_and_recharge_docked_ships() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        AccountResources::<Test>::insert(1, AsteroidKind::Gold, 200);
+        for (x, class) in [(2, ShipClass::Standard), (6, ShipClass::Standard)] {
+            assert_ok!(Template::start_game(
+                RuntimeOrigin::signed(1),
+                coord(x, 2),
+                0,
+                class
+            ));
+        }
+
+        assert_ok!(Template::build_station(
+            RuntimeOrigin::signed(1),
+            0,
+            StationOutput::Gold
+        ));
+        assert_noop!(
+            Template::build_station(RuntimeOrigin::signed(1), 0, StationOutput::Energy),
+            Error::<Test>::StationExists
+        );
+        assert_ok!(Template::build_station(
+            RuntimeOrigin::signed(1),
+            1,
+            StationOutput::Energy
+        ));
+        assert_eq!(AccountResources::<Test>::get(1, AsteroidKind::Gold), 100);
+        assert_eq!(StationsCount::<Test>::get(1), 2);
+
+        assert_noop!(
+            Template::upgrade_station(RuntimeOrigin::signed(2), coord(2, 2)),
+            Error::<Test>::NotStationOwner
+        );
+        assert_ok!(Template::upgrade_station(
+            RuntimeOrigin::signed(1),
+            coord(2, 2)
+        ));
+        assert_eq!(Stations::<Test>::get(coord(2, 2)).unwrap().level, 2);
+        assert_eq!(AccountResources::<Test>::get(1, AsteroidKind::Gold), 0);
+
+        // Ship 1 leaves its Energy station, so the energy is stored until it comes back
+        let mut ship = ActiveShips::<Test>::get(1, 1).unwrap();
+        ship.pos = coord(7, 2);
+        ActiveShips::<Test>::insert(1, 1, ship);
+        let energy = ActiveShips::<Test>::get(1, 0).unwrap().energy;
+        let depletion = ActiveShips::<Test>::get(1, 0)
+            .unwrap()
+            .stats
+            .energy_depletion;
+
+        run_block(11);
+        System::assert_has_event(
+            Event::StationYielded {
+                coord: coord(2, 2),
+                owner: 1,
+                gold: 2,
+                energy: 10,
+            }
+            .into(),
+        );
+        assert_eq!(AccountResources::<Test>::get(1, AsteroidKind::Gold), 2);
+        assert_eq!(
+            ActiveShips::<Test>::get(1, 0).unwrap().energy,
+            energy + 10 - depletion
+        );
+        assert_eq!(
+            Stations::<Test>::get(coord(6, 2)).unwrap().stored_energy,
+            20
+        );
+        assert_eq!(Template::get_stations().len(), 2);
+
+        let mut ship = ActiveShips::<Test>::get(1, 1).unwrap();
+        ship.pos = coord(6, 2);
+        ActiveShips::<Test>::insert(1, 1, ship);
+        run_block(21);
+        System::assert_has_event(
+            Event::StationYielded {
+                coord: coord(6, 2),
+                owner: 1,
+                gold: 0,
+                energy: 45,
+            }
+            .into(),
+        );
+        assert_eq!(Stations::<Test>::get(coord(6, 2)).unwrap().stored_energy, 0);
+    });
+}
+
+#[test]
+fn migration_moves_the_old_layout_into_the_main_game() {
+    use crate::migrations::{v0, MigrateToV1};
+    use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+
+    new_test_ext().execute_with(|| {
+        // The chain being upgraded had no genesis skins and base
+        let _ = Skins::<Test>::clear(u32::MAX, None);
+        let _ = BaseTiles::<Test>::clear(u32::MAX, None);
+        StorageVersion::new(0).put::<Template>();
+        v0::PlayersCount::<Test>::put(1);
+        v0::DotPrizePool::<Test>::put(40);
+        v0::DotEmittedTotal::<Test>::put(5);
+        v0::Asteroids::<Test>::insert(coord(3, 4), (AsteroidKind::Gold, 9));
+        v0::ActiveShips::<Test>::insert(
+            1,
+            v0::Starship {
+                pos: coord(2, 2),
+                energy: 42,
+                nft_skin: 0,
+            },
+        );
+        v0::Flights::<Test>::insert(
+            1,
+            Flight {
+                from: coord(2, 2),
+                to: coord(3, 4),
+                start: 1,
+                end: 3,
+            },
+        );
+
+        MigrateToV1::<Test>::on_runtime_upgrade();
+
+        assert_eq!(Template::on_chain_storage_version(), 1);
+        assert_eq!(PlayersCount::<Test>::get(MAIN_GAME), 1);
+        assert_eq!(DotPrizePool::<Test>::get(MAIN_GAME), 40);
+        assert_eq!(DotEmittedTotal::<Test>::get(MAIN_GAME), 5);
+        assert_eq!(
+            Asteroids::<Test>::get(MAIN_GAME, coord(3, 4)),
+            Some((AsteroidKind::Gold, 9))
+        );
+        let ship = ActiveShips::<Test>::get(1, 0).unwrap();
+        assert_eq!(
+            (ship.game, ship.pos, ship.energy),
+            (MAIN_GAME, coord(2, 2), 42)
+        );
+        assert_eq!(Flights::<Test>::get(1, 0).unwrap().end, 3);
+        for (skin_id, skin) in default_skins() {
+            assert_eq!(Skins::<Test>::get(skin_id), Some(skin));
+        }
+        assert!(BaseTiles::<Test>::contains_key(default_base()));
+
+        // A second run leaves the migrated state alone
+        MigrateToV1::<Test>::on_runtime_upgrade();
+        assert_eq!(ActiveShips::<Test>::get(1, 0).unwrap().energy, 42);
+    });
+}
@@ -1,6 +1,9 @@
-use frame_support::runtime_print;
+use codec::Encode;
+use frame_support::{runtime_print, Blake2_256, StorageHasher};
 
-use crate::Coord;
+use alloc::vec::Vec;
+
+use crate::{Coord, MapWrap};
 
 pub fn get_hash_u32<T: frame_system::Config>() -> u32 {
     let hash = <frame_system::Pallet<T>>::parent_hash();
@@ -8,7 +11,18 @@ pub fn get_hash_u32<T: frame_system::Config>() -> u32 {
     u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
 }
 
+/// Hash of `value` mixed with the parent hash. Unlike an index into a list, it does not depend on
+/// the order in which the block author included the extrinsics that produced `value`.
+pub fn get_keyed_hash<T: frame_system::Config, V: Encode>(value: V) -> [u8; 32] {
+    let hash = <frame_system::Pallet<T>>::parent_hash();
+    Blake2_256::hash(&(hash, value).encode())
+}
+
 fn get_block_randomized_value(seed: u32, skip: u32, max: u32) -> u32 {
+    // An empty range has no values, this keeps a zero `max` from panicking in `on_initialize`
+    if max == 0 {
+        return 0;
+    }
     let skip = if skip == 0 { 1 } else { skip };
     let new_seed = seed / skip;
     new_seed % max
@@ -35,8 +49,222 @@ pub fn get_random<T: frame_system::Config>(max: u32, index: u32) -> u32 {
     result
 }
 
-pub fn get_distance(coord1: Coord, coord2: Coord) -> u32 {
-    let dx = (coord1.x as i32 - coord2.x as i32).abs() as u32;
-    let dy = (coord1.y as i32 - coord2.y as i32).abs() as u32;
-    dx + dy
+/// Signed distance from `a` to `b` along one axis. On a toroidal map of size `wrap`
+/// it is the short way around, which may cross the edge of the map.
+fn axis_delta(a: u32, b: u32, wrap: Option<u32>) -> i64 {
+    let delta = b as i64 - a as i64;
+    match wrap {
+        Some(size) if size > 0 => {
+            let size = size as i64;
+            let delta = delta.rem_euclid(size);
+            if delta * 2 > size {
+                delta - size
+            } else {
+                delta
+            }
+        }
+        _ => delta,
+    }
+}
+
+/// Brings a position that went past the edge of a toroidal map of size `wrap` back onto it.
+fn wrap_axis(value: i64, wrap: Option<u32>) -> u32 {
+    match wrap {
+        Some(size) if size > 0 => value.rem_euclid(size as i64) as u32,
+        _ => value as u32,
+    }
+}
+
+/// Manhattan distance, `wrap` is the size of a toroidal map and `None` on a bounded one.
+pub fn get_distance(coord1: Coord, coord2: Coord, wrap: Option<u32>) -> u32 {
+    let dx = axis_delta(coord1.x, coord2.x, wrap).unsigned_abs();
+    let dy = axis_delta(coord1.y, coord2.y, wrap).unsigned_abs();
+    (dx + dy) as u32
+}
+
+/// How many map widths Unity adds to `to` on each axis to lerp the short way around a toroidal map.
+pub fn get_wrap(from: &Coord, to: &Coord, wrap: Option<u32>) -> MapWrap {
+    let Some(size) = wrap.filter(|size| *size > 0) else {
+        return MapWrap::default();
+    };
+    let axis = |a: u32, b: u32| -> i8 {
+        let short = axis_delta(a, b, wrap);
+        let direct = b as i64 - a as i64;
+        ((short - direct) / size as i64) as i8
+    };
+
+    MapWrap {
+        x: axis(from.x, to.x),
+        y: axis(from.y, to.y),
+    }
+}
+
+/// The eight tiles around `coord`. On a bounded map the ones past the top or left edge are left out.
+pub fn get_adjacent(coord: &Coord, wrap: Option<u32>) -> Vec<Coord> {
+    let mut adjacent = Vec::new();
+    for dx in -1i64..=1 {
+        for dy in -1i64..=1 {
+            if dx == 0 && dy == 0 {
+                continue;
+            }
+            let (x, y) = (i64::from(coord.x) + dx, i64::from(coord.y) + dy);
+            if wrap.is_none() && (x < 0 || y < 0) {
+                continue;
+            }
+            adjacent.push(Coord {
+                x: wrap_axis(x, wrap),
+                y: wrap_axis(y, wrap),
+            });
+        }
+    }
+    adjacent
+}
+
+/// Whether bit `x` is set in a row of a tile bitmap: bit `x % 8` of byte `x / 8`.
+pub fn is_bit_set(row: &[u8], x: u32) -> bool {
+    row.get((x / 8) as usize)
+        .is_some_and(|bits| bits & (1 << (x % 8)) != 0)
+}
+
+/// Picks an index with a chance proportional to its weight, `None` if all weights are zero.
+pub fn pick_weighted<T: frame_system::Config>(weights: &[u32], index: u32) -> Option<usize> {
+    let total = weights
+        .iter()
+        .fold(0u32, |total, weight| total.saturating_add(*weight));
+    if total == 0 {
+        return None;
+    }
+
+    let mut roll = get_random::<T>(total, index);
+    for (i, weight) in weights.iter().enumerate() {
+        if roll < *weight {
+            return Some(i);
+        }
+        roll -= weight;
+    }
+    None
+}
+
+/// The tile next to `from` in the direction of `to`, moving diagonally when both axes differ.
+pub fn step_towards(from: &Coord, to: &Coord, wrap: Option<u32>) -> Coord {
+    let step =
+        |a: u32, b: u32| -> u32 { wrap_axis(a as i64 + axis_delta(a, b, wrap).signum(), wrap) };
+
+    Coord {
+        x: step(from.x, to.x),
+        y: step(from.y, to.y),
+    }
+}
+
+/// The tile of a `size` map closest to `coord`.
+pub fn clamp_to_map(coord: &Coord, size: u32) -> Coord {
+    Coord {
+        x: coord.x.min(size - 1),
+        y: coord.y.min(size - 1),
+    }
+}
+
+/// Tile with the lowest coordinates of the `size` x `size` region `coord` lies in.
+pub fn get_region(coord: &Coord, size: u32) -> Coord {
+    Coord {
+        x: coord.x - coord.x % size,
+        y: coord.y - coord.y % size,
+    }
+}
+
+/// `count` tiles spread evenly over the diamond of tiles `radius` steps away from `center`,
+/// so every tile is equally far from the center. Starting positions of a match.
+pub fn get_start_positions(center: &Coord, radius: u32, count: u32) -> Vec<Coord> {
+    let perimeter = (4 * radius).max(1);
+    (0..count)
+        .map(|index| {
+            let step = index * perimeter / count.max(1);
+            let (side, offset) = (step / radius.max(1), step % radius.max(1));
+            match side {
+                0 => Coord {
+                    x: center.x + radius - offset,
+                    y: center.y + offset,
+                },
+                1 => Coord {
+                    x: center.x - offset,
+                    y: center.y + radius - offset,
+                },
+                2 => Coord {
+                    x: center.x - radius + offset,
+                    y: center.y - offset,
+                },
+                _ => Coord {
+                    x: center.x + offset,
+                    y: center.y - radius + offset,
+                },
+            }
+        })
+        .collect()
+}
+
+/// Position of a ship that flies from `from` to `to` in `duration` blocks, after `elapsed` blocks.
+/// Rounded down to the grid, mirroring the linear interpolation done by Unity.
+pub fn get_interpolated_position(
+    from: &Coord,
+    to: &Coord,
+    elapsed: u32,
+    duration: u32,
+    wrap: Option<u32>,
+) -> Coord {
+    if duration == 0 || elapsed >= duration {
+        return to.clone();
+    }
+
+    let lerp = |a: u32, b: u32| -> u32 {
+        let delta = axis_delta(a, b, wrap) * elapsed as i64 / duration as i64;
+        wrap_axis(a as i64 + delta, wrap)
+    };
+
+    Coord {
+        x: lerp(from.x, to.x),
+        y: lerp(from.y, to.y),
+    }
+}
+
+/// All grid cells crossed by the straight line from `from` to `to`, both ends included (Bresenham).
+/// On a toroidal map the line takes the short way and continues on the other side of an edge.
+/// The cells are produced lazily, so a caller can stop the walk early.
+pub fn get_line(from: &Coord, to: &Coord, wrap: Option<u32>) -> impl Iterator<Item = Coord> {
+    let (mut x, mut y) = (from.x as i64, from.y as i64);
+    let (x1, y1) = (
+        x + axis_delta(from.x, to.x, wrap),
+        y + axis_delta(from.y, to.y, wrap),
+    );
+
+    let dx = (x1 - x).abs();
+    let dy = -(y1 - y).abs();
+    let sx = if x < x1 { 1 } else { -1 };
+    let sy = if y < y1 { 1 } else { -1 };
+    let mut err = dx + dy;
+    let mut done = false;
+
+    core::iter::from_fn(move || {
+        if done {
+            return None;
+        }
+        let cell = Coord {
+            x: wrap_axis(x, wrap),
+            y: wrap_axis(y, wrap),
+        };
+        if x == x1 && y == y1 {
+            done = true;
+            return Some(cell);
+        }
+
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += sy;
+        }
+        Some(cell)
+    })
 }
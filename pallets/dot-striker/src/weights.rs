@@ -18,6 +18,40 @@ pub trait WeightInfo {
 	fn admin_set_map_size() -> Weight;
 	fn admin_set_max_asteroids_count() -> Weight;
 	fn admin_reset_game() -> Weight;
+	fn redirect_flight() -> Weight;
+	fn plan_route() -> Weight;
+	fn set_collection_mode() -> Weight;
+	fn admin_set_sweep_collect_cap() -> Weight;
+	fn upgrade_ship() -> Weight;
+	fn admin_set_skin() -> Weight;
+	fn admin_remove_skin() -> Weight;
+	fn admin_set_class_stats() -> Weight;
+	fn deposit_cargo() -> Weight;
+	fn admin_set_base_tile() -> Weight;
+	fn admin_set_wreck_share() -> Weight;
+	fn admin_set_wreck_ttl() -> Weight;
+	fn admin_set_respawn_cooldown() -> Weight;
+	fn admin_set_lives_per_season() -> Weight;
+	fn admin_start_season() -> Weight;
+	fn admin_set_black_holes() -> Weight;
+	fn admin_set_hazard_zones() -> Weight;
+	fn admin_set_blocked_tiles() -> Weight;
+	fn admin_upload_map() -> Weight;
+	fn admin_set_map_topology() -> Weight;
+	fn create_arena() -> Weight;
+	fn join_arena() -> Weight;
+	fn close_arena() -> Weight;
+	fn create_tournament() -> Weight;
+	fn register_for_tournament() -> Weight;
+	fn join_queue() -> Weight;
+	fn leave_queue() -> Weight;
+	fn challenge() -> Weight;
+	fn accept_challenge() -> Weight;
+	fn claim_tile() -> Weight;
+	fn admin_set_territory_tax() -> Weight;
+	fn build_station() -> Weight;
+	fn upgrade_station() -> Weight;
+	fn resolve_claim() -> Weight;
 }
 
 /// Weights for pallet_template using default Substrate weights.
@@ -40,27 +74,31 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	}
 
 	fn try_to_collect_resource() -> Weight {
-		// Reads: Flights, ActiveShips, Asteroids
-		// Writes: could be multiple depending on asteroid kind
-		// Assume: +1 write for ship or resource update, +1 for asteroid removal
+		// Reads: Flights, ActiveShips, Asteroids, PendingClaims
+		// Writes: PendingClaims
+		// The asteroid is collected in `on_finalize`, which is paid for here as well (see `resolve_claim`)
 		Weight::from_parts(18_000_000, 0)
-			.saturating_add(T::DbWeight::get().reads(3))
-			.saturating_add(T::DbWeight::get().writes(2))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(Self::resolve_claim())
 	}
 
 	fn start_game() -> Weight {
-		// Reads: ActiveShips, MapSize, AccountResources (if NFT used)
+		// Reads: RespawnAt, LivesPerSeason, LostShips, ActiveShips, MapSize, AccountResources (if NFT used)
 		// Writes: ActiveShips, DotPrizePool, PlayersCount
-		// Conservative estimate: 3 reads, 3 writes
+		// Conservative estimate: 6 reads, 3 writes
 		Weight::from_parts(14_000_000, 0)
-			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().reads(6))
 			.saturating_add(T::DbWeight::get().writes(3))
 	}
 
 	fn admin_set_map_size() -> Weight {
-		// Writes: MapSize
-		Weight::from_parts(4_000_000, 0)
-			.saturating_add(T::DbWeight::get().writes(1))
+		// Reads: MapSize, MapCleanupProgress, BlackHoles, HazardZones, SpawnZones
+		// Reads and writes on shrink: BlockedRows (up to MAX_MAP_ROWS = 256), BaseTiles (~16)
+		// Writes: MapSize, BlackHoles, HazardZones, SpawnZones, MapCleanupProgress
+		Weight::from_parts(20_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(277))
+			.saturating_add(T::DbWeight::get().writes(277))
 	}
 
 	fn admin_set_max_asteroids_count() -> Weight {
@@ -77,6 +115,255 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 		Weight::from_parts(5_000_000, 0)
 			.saturating_add(T::DbWeight::get().writes(2 * ops))
 	}
+
+	fn redirect_flight() -> Weight {
+		// Reads: Flights, ActiveShips
+		// Writes: ActiveShips (position and energy), Flights
+		Weight::from_parts(12_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+
+	fn plan_route() -> Weight {
+		// Reads: Flights, ActiveShips
+		// Writes: ActiveShips (energy), Flights, Routes
+		Weight::from_parts(14_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+
+	fn set_collection_mode() -> Weight {
+		// Reads: ActiveShips
+		// Writes: ActiveShips
+		Weight::from_parts(8_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+
+	fn admin_set_sweep_collect_cap() -> Weight {
+		// Writes: SweepCollectCap
+		Weight::from_parts(4_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+
+	fn upgrade_ship() -> Weight {
+		// Reads: ShipUpgrades, AccountResources
+		// Writes: ShipUpgrades, AccountResources
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+
+	fn admin_set_skin() -> Weight {
+		// Writes: Skins
+		Weight::from_parts(5_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+
+	fn admin_remove_skin() -> Weight {
+		// Reads: Skins
+		// Writes: Skins
+		Weight::from_parts(5_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+
+	fn admin_set_class_stats() -> Weight {
+		// Writes: ClassStats
+		Weight::from_parts(5_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+
+	fn deposit_cargo() -> Weight {
+		// Reads: Flights, ActiveShips, BaseTiles
+		// Writes: ActiveShips, AccountResources (Gold and DOT)
+		Weight::from_parts(12_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+
+	fn admin_set_base_tile() -> Weight {
+		// Writes: BaseTiles
+		Weight::from_parts(4_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+
+	fn admin_set_wreck_share() -> Weight {
+		// Writes: WreckShare
+		Weight::from_parts(4_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+
+	fn admin_set_wreck_ttl() -> Weight {
+		// Writes: WreckTtl
+		Weight::from_parts(4_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+
+	fn admin_set_respawn_cooldown() -> Weight {
+		// Writes: RespawnCooldownBlocks
+		Weight::from_parts(4_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+
+	fn admin_set_lives_per_season() -> Weight {
+		// Writes: LivesPerSeason
+		Weight::from_parts(4_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+
+	fn admin_start_season() -> Weight {
+		// Reads: Season
+		// Writes: Season
+		Weight::from_parts(5_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+
+	fn admin_set_black_holes() -> Weight {
+		// Writes: BlackHoles
+		Weight::from_parts(4_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+
+	fn admin_set_hazard_zones() -> Weight {
+		// Writes: HazardZones
+		Weight::from_parts(5_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+
+	fn admin_set_blocked_tiles() -> Weight {
+		// Per tile: 1 read and 1 write of the BlockedRows row
+		// Assume the call is full: MAX_BLOCKED_TILES_PER_CALL = 256
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(256))
+			.saturating_add(T::DbWeight::get().writes(256))
+	}
+
+	fn admin_upload_map() -> Weight {
+		// Writes: MapSize, SpawnZones
+		// Clears and writes BlockedRows (up to MAX_MAP_ROWS = 256) and BaseTiles (up to MAX_BASE_TILES = 16)
+		Weight::from_parts(20_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(546))
+	}
+
+	fn admin_set_map_topology() -> Weight {
+		// Writes: Topology
+		Weight::from_parts(4_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+
+	fn create_arena() -> Weight {
+		// Reads: Arenas (up to MAX_ARENAS = 16) with their Matches and Duels entries, NextArenaId
+		// Writes: NextArenaId, Arenas
+		Weight::from_parts(15_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(49))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+
+	fn join_arena() -> Weight {
+		// Reads: Arenas, PlayersCount, plus the reads of start_game
+		// Writes: ActiveShips, DotPrizePool, PlayersCount
+		Weight::from_parts(20_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(8))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+
+	fn close_arena() -> Weight {
+		// Reads: Arenas, PlayersCount
+		// Writes: Arenas, DotPrizePool, DotEmittedTotal, LastNftSpawnBlock, PlayersCount
+		// Clears: Asteroids and Wrecks of the arena (up to MAX_ARENA_ASTEROIDS = 100 each)
+		Weight::from_parts(20_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(205))
+	}
+
+	fn create_tournament() -> Weight {
+		// Reads and writes of create_arena
+		// Writes: Tournaments
+		Weight::from_parts(20_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(49))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+
+	fn register_for_tournament() -> Weight {
+		// Reads: Tournaments, Arenas, AccountResources
+		// Writes: AccountResources, Tournaments
+		Weight::from_parts(15_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+
+	fn join_queue() -> Weight {
+		// Reads: MatchQueue (per mode), RespawnAt, LivesPerSeason, Season, LostShips, ActiveShips (per fleet slot)
+		// Writes: MatchQueue
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(10))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+
+	fn leave_queue() -> Weight {
+		// Reads: MatchQueue
+		// Writes: MatchQueue
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+
+	fn challenge() -> Weight {
+		// Reads: AccountResources, NextChallengeId, ChallengeExpiries
+		// Writes: AccountResources, NextChallengeId, Challenges, ChallengeExpiries
+		Weight::from_parts(15_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+
+	fn accept_challenge() -> Weight {
+		// Reads: Challenges, Arenas with their Matches and Duels entries, AccountResources, NextArenaId,
+		// ship placement of both duelists
+		// Writes: AccountResources, Challenges, NextArenaId, Arenas, Duels, both ships
+		Weight::from_parts(40_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(54))
+			.saturating_add(T::DbWeight::get().writes(9))
+	}
+
+	fn claim_tile() -> Weight {
+		// Reads: MapSize, Territories, AccountResources
+		// Writes: AccountResources (both owners), Territories
+		Weight::from_parts(15_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+
+	fn admin_set_territory_tax() -> Weight {
+		// Writes: TerritoryTax
+		Weight::from_parts(5_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+
+	fn build_station() -> Weight {
+		// Reads: Flights, ActiveShips, Stations, StationsCount, AccountResources
+		// Writes: AccountResources, Stations, StationsCount
+		Weight::from_parts(15_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+
+	fn upgrade_station() -> Weight {
+		// Reads: Stations, AccountResources
+		// Writes: AccountResources, Stations
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+
+	fn resolve_claim() -> Weight {
+		// Reads: Asteroids (contains + get), ActiveShips, Wrecks, Territories, TerritoryTax, Tournaments, Duels
+		// Writes: ActiveShips, AccountResources (territory owner), MatchScores, Wrecks, asteroid removal (Asteroids, Wrecks, DotEmittedTotal)
+		Weight::from_parts(20_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(8))
+			.saturating_add(T::DbWeight::get().writes(7))
+	}
 }
 
 // For backwards compatibility and testing
@@ -95,19 +382,21 @@ impl WeightInfo for () {
 
 	fn try_to_collect_resource() -> Weight {
 		Weight::from_parts(18_000_000, 0)
-			.saturating_add(RocksDbWeight::get().reads(3))
-			.saturating_add(RocksDbWeight::get().writes(2))
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().writes(1))
+			.saturating_add(Self::resolve_claim())
 	}
 
 	fn start_game() -> Weight {
 		Weight::from_parts(14_000_000, 0)
-			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().reads(6))
 			.saturating_add(RocksDbWeight::get().writes(3))
 	}
 
 	fn admin_set_map_size() -> Weight {
-		Weight::from_parts(4_000_000, 0)
-			.saturating_add(RocksDbWeight::get().writes(1))
+		Weight::from_parts(20_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(277))
+			.saturating_add(RocksDbWeight::get().writes(277))
 	}
 
 	fn admin_set_max_asteroids_count() -> Weight {
@@ -120,4 +409,195 @@ impl WeightInfo for () {
 		Weight::from_parts(5_000_000, 0)
 			.saturating_add(RocksDbWeight::get().writes(2 * ops))
 	}
+
+	fn redirect_flight() -> Weight {
+		Weight::from_parts(12_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+
+	fn plan_route() -> Weight {
+		Weight::from_parts(14_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+
+	fn set_collection_mode() -> Weight {
+		Weight::from_parts(8_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+
+	fn admin_set_sweep_collect_cap() -> Weight {
+		Weight::from_parts(4_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+
+	fn upgrade_ship() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+
+	fn admin_set_skin() -> Weight {
+		Weight::from_parts(5_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+
+	fn admin_remove_skin() -> Weight {
+		Weight::from_parts(5_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+
+	fn admin_set_class_stats() -> Weight {
+		Weight::from_parts(5_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+
+	fn deposit_cargo() -> Weight {
+		Weight::from_parts(12_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+
+	fn admin_set_base_tile() -> Weight {
+		Weight::from_parts(4_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+
+	fn admin_set_wreck_share() -> Weight {
+		Weight::from_parts(4_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+
+	fn admin_set_wreck_ttl() -> Weight {
+		Weight::from_parts(4_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+
+	fn admin_set_respawn_cooldown() -> Weight {
+		Weight::from_parts(4_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+
+	fn admin_set_lives_per_season() -> Weight {
+		Weight::from_parts(4_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+
+	fn admin_start_season() -> Weight {
+		Weight::from_parts(5_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+
+	fn admin_set_black_holes() -> Weight {
+		Weight::from_parts(4_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+
+	fn admin_set_hazard_zones() -> Weight {
+		Weight::from_parts(5_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+
+	fn admin_set_blocked_tiles() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(256))
+			.saturating_add(RocksDbWeight::get().writes(256))
+	}
+
+	fn admin_upload_map() -> Weight {
+		Weight::from_parts(20_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(546))
+	}
+
+	fn admin_set_map_topology() -> Weight {
+		Weight::from_parts(4_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+
+	fn create_arena() -> Weight {
+		Weight::from_parts(15_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(49))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+
+	fn join_arena() -> Weight {
+		Weight::from_parts(20_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(8))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+
+	fn close_arena() -> Weight {
+		Weight::from_parts(20_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(205))
+	}
+
+	fn create_tournament() -> Weight {
+		Weight::from_parts(20_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(49))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+
+	fn register_for_tournament() -> Weight {
+		Weight::from_parts(15_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+
+	fn join_queue() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(10))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+
+	fn leave_queue() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+
+	fn challenge() -> Weight {
+		Weight::from_parts(15_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().writes(4))
+	}
+
+	fn accept_challenge() -> Weight {
+		Weight::from_parts(40_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(54))
+			.saturating_add(RocksDbWeight::get().writes(9))
+	}
+
+	fn claim_tile() -> Weight {
+		Weight::from_parts(15_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+
+	fn admin_set_territory_tax() -> Weight {
+		Weight::from_parts(5_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+
+	fn build_station() -> Weight {
+		Weight::from_parts(15_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(5))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+
+	fn upgrade_station() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+
+	fn resolve_claim() -> Weight {
+		Weight::from_parts(20_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(8))
+			.saturating_add(RocksDbWeight::get().writes(7))
+	}
 }
\ No newline at end of file